@@ -0,0 +1,57 @@
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    /// Probability (e.g. of a mutation) outside of `0.0..=1.0`
+    InvalidChance(f32),
+
+    EmptyPopulation,
+
+    /// Roulette wheel can't pick anybody when nobody has scored
+    ZeroFitness,
+
+    /// Roulette wheel can't weigh individuals by negative (or NaN)
+    /// fitness
+    InvalidFitness,
+
+    /// Tournament selection with no contestants
+    InvalidTournamentSize,
+
+    /// Truncation selection's fraction outside of `(0.0, 1.0]`
+    InvalidFraction(f32),
+
+    /// K-point crossover with no points to cut at
+    InvalidPointCount,
+
+    /// Self-adaptive mutation got a chromosome that can't consist of
+    /// genes followed by their step sizes (i.e. of an odd length)
+    MissingStepSizes,
+
+    /// Parameter of a crossover or mutation method that's out of its
+    /// range (e.g. a negative sigma)
+    InvalidParameter { name: &'static str, value: f32 },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidChance(chance) => {
+                write!(f, "chance must be within 0.0..=1.0, got {}", chance)
+            }
+            Self::EmptyPopulation => write!(f, "got an empty population"),
+            Self::ZeroFitness => write!(f, "got a population where nobody has scored"),
+            Self::InvalidFitness => write!(f, "got a negative or NaN fitness"),
+            Self::InvalidTournamentSize => {
+                write!(f, "tournament must have at least one contestant")
+            }
+            Self::InvalidFraction(fraction) => {
+                write!(f, "fraction must be within (0.0, 1.0], got {}", fraction)
+            }
+            Self::InvalidPointCount => write!(f, "crossover must have at least one point"),
+            Self::MissingStepSizes => write!(f, "got a chromosome without step sizes"),
+            Self::InvalidParameter { name, value } => write!(f, "invalid {}: {}", name, value),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
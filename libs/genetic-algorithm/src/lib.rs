@@ -1,8 +1,16 @@
+pub use self::{error::*, neat::*};
+
 use std::ops::Index;
 use rand::RngCore;
 // use rand::Rng;
+use rand::distributions::{Distribution, Uniform, WeightedError};
 use rand::seq::SliceRandom;
+use rand_distr::StandardNormal;
 use std::iter::FromIterator;
+use serde::{Deserialize, Serialize};
+
+mod error;
+mod neat;
 
 pub struct RouletteWheelSelection;
 
@@ -16,6 +24,10 @@ pub struct GeneticAlgorithm<S> {
     selection_method: S,
     crossover_method: Box<dyn CrossoverMethod>,
     mutation_method: Box<dyn MutationMethod>,
+
+    /// Number of the fittest individuals that get copied into the next
+    /// population unchanged
+    elite_count: usize,
 }
 
 pub trait Individual {
@@ -40,6 +52,36 @@ pub trait CrossoverMethod {
 
 pub trait MutationMethod {
     fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome);
+
+    /// Mutates a child that's going to be a part of given generation;
+    /// by default the generation is not taken into account, but e.g.
+    /// `AnnealedMutation` uses it to calm down as the evolution goes on.
+    fn mutate_in_generation(
+        &self,
+        rng: &mut dyn RngCore,
+        child: &mut Chromosome,
+        _generation: usize,
+    ) {
+        self.mutate(rng, child);
+    }
+}
+
+impl<M> MutationMethod for Box<M>
+where
+    M: MutationMethod + ?Sized,
+{
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        (**self).mutate(rng, child);
+    }
+
+    fn mutate_in_generation(
+        &self,
+        rng: &mut dyn RngCore,
+        child: &mut Chromosome,
+        generation: usize,
+    ) {
+        (**self).mutate_in_generation(rng, child, generation);
+    }
 }
 
 impl Chromosome {
@@ -82,30 +124,239 @@ impl IntoIterator for Chromosome {
 }
 
 pub trait SelectionMethod {
+    /// Picks an individual out of the population; fails when there's
+    /// nobody to pick from (e.g. the population is empty) or the
+    /// fitnesses can't be used (e.g. one of them is NaN).
+    fn try_select<'a, I>(
+       &self,
+       rng: &mut dyn RngCore,
+       population: &'a [I],
+    ) -> Result<&'a I, Error>
+    where
+        I: Individual;
+
     fn select<'a, I>(
        &self,
        rng: &mut dyn RngCore,
        population: &'a [I],
     ) -> &'a I
     where
-        I: Individual;
+        I: Individual,
+    {
+        self.try_select(rng, population)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as `try_select()`, but also given the population ordered
+    /// from the least to the most fit - `GeneticAlgorithm` sorts it once
+    /// per generation, so that methods relying on that order don't have
+    /// to sort it again for each parent.
+    fn try_select_presorted<'a, I>(
+       &self,
+       rng: &mut dyn RngCore,
+       population: &'a [I],
+       _sorted: &[&'a I],
+    ) -> Result<&'a I, Error>
+    where
+        I: Individual,
+    {
+        self.try_select(rng, population)
+    }
 }
 
 impl SelectionMethod for RouletteWheelSelection {
-    fn select<'a, I>(
+    fn try_select<'a, I>(
        &self,
        rng: &mut dyn RngCore,
        population: &'a [I],
-    ) -> &'a I
+    ) -> Result<&'a I, Error>
     where
         I: Individual,
     {
         population
             .choose_weighted(rng, |individual| individual.fitness())
-            .expect("got an empty population")
+            .map_err(|err| match err {
+                WeightedError::NoItem => Error::EmptyPopulation,
+                WeightedError::AllWeightsZero => Error::ZeroFitness,
+                WeightedError::InvalidWeight | WeightedError::TooMany => Error::InvalidFitness,
+            })
+    }
+}
+
+/// Picks `size` random individuals and returns the fittest one among
+/// them; the larger the tournament, the higher the selection pressure.
+#[derive(Clone, Debug)]
+pub struct TournamentSelection {
+    size: usize,
+}
+
+impl TournamentSelection {
+    pub fn new(size: usize) -> Self {
+        Self::try_new(size).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(size: usize) -> Result<Self, Error> {
+        if size == 0 {
+            return Err(Error::InvalidTournamentSize);
+        }
+
+        Ok(Self { size })
+    }
+}
+
+impl SelectionMethod for TournamentSelection {
+    fn try_select<'a, I>(
+       &self,
+       rng: &mut dyn RngCore,
+       population: &'a [I],
+    ) -> Result<&'a I, Error>
+    where
+        I: Individual,
+    {
+        let contestants = (0..self.size)
+            .map(|_| population.choose(rng).ok_or(Error::EmptyPopulation))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if contestants.iter().any(|contestant| contestant.fitness().is_nan()) {
+            return Err(Error::InvalidFitness);
+        }
+
+        Ok(contestants
+            .into_iter()
+            .max_by(|a, b| a.fitness().partial_cmp(&b.fitness()).unwrap())
+            .unwrap())
+    }
+}
+
+/// Like roulette wheel, but weighs individuals by their rank (1 for the
+/// least fit one, N for the fittest one) instead of their raw fitness -
+/// so it works for zero and negative fitnesses, too.
+#[derive(Clone, Debug)]
+pub struct RankSelection;
+
+impl RankSelection {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl SelectionMethod for RankSelection {
+    fn try_select<'a, I>(
+       &self,
+       rng: &mut dyn RngCore,
+       population: &'a [I],
+    ) -> Result<&'a I, Error>
+    where
+        I: Individual,
+    {
+        self.try_select_presorted(rng, population, &try_sorted_by_fitness(population)?)
+    }
+
+    fn try_select_presorted<'a, I>(
+       &self,
+       rng: &mut dyn RngCore,
+       _population: &'a [I],
+       sorted: &[&'a I],
+    ) -> Result<&'a I, Error>
+    where
+        I: Individual,
+    {
+        if sorted.is_empty() {
+            return Err(Error::EmptyPopulation);
+        }
+
+        // The `n` least fit individuals weigh `n * (n + 1) / 2` altogether,
+        // so the picked one can be found by bisecting over `n`, without
+        // having to sum up the weights each time
+        let cumulative_weight = |idx: usize| (idx + 1) * (idx + 2) / 2;
+        let pick = Uniform::new(0, cumulative_weight(sorted.len() - 1)).sample(rng);
+
+        let (mut lo, mut hi) = (0, sorted.len() - 1);
+
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+
+            if cumulative_weight(mid) > pick {
+                hi = mid;
+            } else {
+                lo = mid + 1;
+            }
+        }
+
+        Ok(sorted[lo])
     }
 }
 
+/// Picks uniformly among the fittest `fraction` of the population.
+#[derive(Clone, Debug)]
+pub struct TruncationSelection {
+    /// Part of the population that's eligible for selection:
+    /// - 0.1 = only the top 10% individuals
+    /// - 1.0 = everybody
+    fraction: f32,
+}
+
+impl TruncationSelection {
+    pub fn new(fraction: f32) -> Self {
+        Self::try_new(fraction).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(fraction: f32) -> Result<Self, Error> {
+        if !(fraction > 0.0 && fraction <= 1.0) {
+            return Err(Error::InvalidFraction(fraction));
+        }
+
+        Ok(Self { fraction })
+    }
+}
+
+impl SelectionMethod for TruncationSelection {
+    fn try_select<'a, I>(
+       &self,
+       rng: &mut dyn RngCore,
+       population: &'a [I],
+    ) -> Result<&'a I, Error>
+    where
+        I: Individual,
+    {
+        self.try_select_presorted(rng, population, &try_sorted_by_fitness(population)?)
+    }
+
+    fn try_select_presorted<'a, I>(
+       &self,
+       rng: &mut dyn RngCore,
+       _population: &'a [I],
+       sorted: &[&'a I],
+    ) -> Result<&'a I, Error>
+    where
+        I: Individual,
+    {
+        let eligible = ((sorted.len() as f32 * self.fraction).ceil() as usize).max(1);
+
+        sorted[sorted.len().saturating_sub(eligible)..]
+            .choose(rng)
+            .copied()
+            .ok_or(Error::EmptyPopulation)
+    }
+}
+
+/// Returns the population ordered from the least to the most fit; fails
+/// when any of the fitnesses is NaN.
+fn try_sorted_by_fitness<I>(population: &[I]) -> Result<Vec<&I>, Error>
+where
+    I: Individual,
+{
+    let mut sorted: Vec<_> = population.iter().collect();
+
+    if sorted.iter().any(|individual| individual.fitness().is_nan()) {
+        return Err(Error::InvalidFitness);
+    }
+
+    sorted.sort_by(|a, b| a.fitness().partial_cmp(&b.fitness()).unwrap());
+
+    Ok(sorted)
+}
+
 use rand::Rng;
 
 #[derive(Clone, Debug)]
@@ -129,90 +380,641 @@ impl CrossoverMethod for UniformCrossover {
         let parent_a = parent_a.iter();
         let parent_b = parent_b.iter();
 
-        parent_a
-            .zip(parent_b)
-            .map(|(&a, &b)| if rng.gen_bool(0.5) { a } else { b })
-            .collect()
+        parent_a
+            .zip(parent_b)
+            .map(|(&a, &b)| if rng.gen_bool(0.5) { a } else { b })
+            .collect()
+    }
+}
+
+/// Takes genes before a random cut point from `parent_a` and the rest
+/// from `parent_b`.
+#[derive(Clone, Debug)]
+pub struct SinglePointCrossover;
+
+impl SinglePointCrossover {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl CrossoverMethod for SinglePointCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        KPointCrossover::new(1).crossover(rng, parent_a, parent_b)
+    }
+}
+
+/// Cuts parents at `k` random points and takes the resulting segments
+/// alternately from `parent_a` and `parent_b`.
+#[derive(Clone, Debug)]
+pub struct KPointCrossover {
+    k: usize,
+}
+
+impl KPointCrossover {
+    pub fn new(k: usize) -> Self {
+        Self::try_new(k).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(k: usize) -> Result<Self, Error> {
+        if k == 0 {
+            return Err(Error::InvalidPointCount);
+        }
+
+        Ok(Self { k })
+    }
+}
+
+impl CrossoverMethod for KPointCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+
+        // Cutting happens *between* genes, so there are `len - 1` places
+        // to cut at
+        let cuts = self.k.min(parent_a.len().saturating_sub(1));
+
+        let mut cut_points: Vec<_> =
+            rand::seq::index::sample(rng, parent_a.len().saturating_sub(1), cuts)
+                .into_iter()
+                .map(|point| point + 1)
+                .collect();
+
+        cut_points.sort_unstable();
+
+        let mut cut_points = cut_points.into_iter().peekable();
+        let mut from_a = true;
+
+        parent_a
+            .iter()
+            .zip(parent_b.iter())
+            .enumerate()
+            .map(|(idx, (&a, &b))| {
+                if cut_points.peek() == Some(&idx) {
+                    cut_points.next();
+                    from_a = !from_a;
+                }
+
+                if from_a { a } else { b }
+            })
+            .collect()
+    }
+}
+
+/// Blend crossover (BLX-α): each child's gene is picked uniformly from
+/// the range spanned by the parents' genes, extended by `alpha` of its
+/// width on both sides.
+#[derive(Clone, Debug)]
+pub struct ArithmeticCrossover {
+    /// How far beyond the parents' genes the child can reach:
+    /// - 0.0 = child's genes lie between the parents' ones
+    /// - 0.5 = range gets extended by half of its width on each side
+    alpha: f32,
+}
+
+impl ArithmeticCrossover {
+    pub fn new(alpha: f32) -> Self {
+        Self::try_new(alpha).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(alpha: f32) -> Result<Self, Error> {
+        check_non_negative("alpha", alpha)?;
+
+        Ok(Self { alpha })
+    }
+}
+
+impl CrossoverMethod for ArithmeticCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+
+        parent_a
+            .iter()
+            .zip(parent_b.iter())
+            .map(|(&a, &b)| {
+                let extent = self.alpha * (a - b).abs();
+                let min = a.min(b) - extent;
+                let max = a.max(b) + extent;
+
+                rng.gen_range(min..=max)
+            })
+            .collect()
+    }
+}
+
+/// Simulated binary crossover (SBX): mimics the spread of children
+/// that single-point crossover yields on binary strings, but for
+/// real-valued genes.
+#[derive(Clone, Debug)]
+pub struct SimulatedBinaryCrossover {
+    /// Distribution index:
+    /// - small values (e.g. 2.0) = children can land far from parents
+    /// - large values (e.g. 20.0) = children stay close to parents
+    eta: f32,
+}
+
+impl SimulatedBinaryCrossover {
+    pub fn new(eta: f32) -> Self {
+        Self::try_new(eta).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(eta: f32) -> Result<Self, Error> {
+        check_non_negative("eta", eta)?;
+
+        Ok(Self { eta })
+    }
+}
+
+impl CrossoverMethod for SimulatedBinaryCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+
+        let exponent = 1.0 / (self.eta + 1.0);
+
+        parent_a
+            .iter()
+            .zip(parent_b.iter())
+            .map(|(&a, &b)| {
+                let u: f32 = rng.gen();
+
+                let beta = if u <= 0.5 {
+                    (2.0 * u).powf(exponent)
+                } else {
+                    (1.0 / (2.0 * (1.0 - u))).powf(exponent)
+                };
+
+                // SBX yields two children - `(1 + beta) / 2 * a + (1 - beta) / 2 * b`
+                // and its mirror image; we pick one of them at random
+                if rng.gen_bool(0.5) {
+                    0.5 * ((1.0 + beta) * a + (1.0 - beta) * b)
+                } else {
+                    0.5 * ((1.0 - beta) * a + (1.0 + beta) * b)
+                }
+            })
+            .collect()
+    }
+}
+
+/// Perturbs genes by a value drawn *uniformly* from `-coeff..coeff`;
+/// for a normally-distributed perturbation, see `NormalMutation`.
+#[derive(Clone, Debug)]
+pub struct GaussianMutation {
+    /// Probability of changing a gene:
+    /// - 0.0 = no genes will be touched
+    /// - 1.0 = all genes will be touched
+    chance: f32,
+
+    /// Magnitude of that change:
+    /// - 0.0 = touched genes will not be modified
+    /// - 3.0 = touched genes will be += or -= by at most 3.0
+    coeff: f32,
+}
+
+impl GaussianMutation {
+    pub fn new(chance: f32, coeff: f32) -> Self {
+        Self::try_new(chance, coeff).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(chance: f32, coeff: f32) -> Result<Self, Error> {
+        check_chance(chance)?;
+
+        Ok(Self { chance, coeff })
+    }
+}
+
+impl MutationMethod for GaussianMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        for gene in child.iter_mut() {
+            let sign = if rng.gen_bool(0.5) { -1.0 } else { 1.0 };
+
+            if rng.gen_bool(self.chance as _) {
+                *gene += sign * self.coeff * rng.gen::<f32>();
+            }
+        }
+    }
+}
+
+/// Perturbs genes by a value drawn from the normal distribution.
+#[derive(Clone, Debug)]
+pub struct NormalMutation {
+    /// Probability of changing a gene:
+    /// - 0.0 = no genes will be touched
+    /// - 1.0 = all genes will be touched
+    chance: f32,
+
+    /// Standard deviation of that change:
+    /// - 0.0 = touched genes will not be modified
+    /// - 0.5 = touched genes will be moved by less than 0.5 in ~68% of
+    ///   the cases, and by less than 1.0 in ~95% of them
+    sigma: f32,
+}
+
+impl NormalMutation {
+    pub fn new(chance: f32, sigma: f32) -> Self {
+        Self::try_new(chance, sigma).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(chance: f32, sigma: f32) -> Result<Self, Error> {
+        check_chance(chance)?;
+        check_non_negative("sigma", sigma)?;
+
+        Ok(Self { chance, sigma })
+    }
+}
+
+impl MutationMethod for NormalMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        for gene in child.iter_mut() {
+            if rng.gen_bool(self.chance as _) {
+                *gene += self.sigma * rng.sample::<f32, _>(StandardNormal);
+            }
+        }
+    }
+}
+
+/// `NormalMutation` whose sigma decays exponentially with each
+/// generation - so that the evolution explores a lot at first and then
+/// gradually settles down on fine-tuning what it has found.
+#[derive(Clone, Debug)]
+pub struct AnnealedMutation {
+    /// Probability of changing a gene
+    chance: f32,
+
+    /// Sigma used for the very first generation
+    initial_sigma: f32,
+
+    /// Factor the sigma gets multiplied by with each generation:
+    /// - 1.0 = no annealing at all
+    /// - 0.99 = sigma halves every ~70 generations
+    decay: f32,
+}
+
+impl AnnealedMutation {
+    pub fn new(chance: f32, initial_sigma: f32, decay: f32) -> Self {
+        Self::try_new(chance, initial_sigma, decay).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(chance: f32, initial_sigma: f32, decay: f32) -> Result<Self, Error> {
+        check_chance(chance)?;
+        check_non_negative("initial_sigma", initial_sigma)?;
+
+        if !(decay > 0.0 && decay <= 1.0) {
+            return Err(Error::InvalidParameter {
+                name: "decay",
+                value: decay,
+            });
+        }
+
+        Ok(Self {
+            chance,
+            initial_sigma,
+            decay,
+        })
+    }
+
+    pub fn sigma(&self, generation: usize) -> f32 {
+        self.initial_sigma * self.decay.powf(generation as f32)
+    }
+}
+
+impl MutationMethod for AnnealedMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        self.mutate_in_generation(rng, child, 0);
+    }
+
+    fn mutate_in_generation(
+        &self,
+        rng: &mut dyn RngCore,
+        child: &mut Chromosome,
+        generation: usize,
+    ) {
+        NormalMutation::new(self.chance, self.sigma(generation)).mutate(rng, child);
+    }
+}
+
+/// Evolution-strategy-style mutation, where each gene has its own step
+/// size that gets evolved alongside it.
+///
+/// Chromosomes are expected to consist of two halves: the genes
+/// themselves, followed by their step sizes (see `with_step_sizes()`);
+/// it's up to the `Individual` to only interpret the first half.
+#[derive(Clone, Debug)]
+pub struct SelfAdaptiveMutation {
+    /// Step size given to each gene by `with_step_sizes()`
+    initial_sigma: f32,
+
+    /// Lower bound for step sizes, so that genes never freeze entirely
+    min_sigma: f32,
+}
+
+impl SelfAdaptiveMutation {
+    pub fn new(initial_sigma: f32, min_sigma: f32) -> Self {
+        Self::try_new(initial_sigma, min_sigma).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Fails unless `0.0 <= min_sigma <= initial_sigma`.
+    pub fn try_new(initial_sigma: f32, min_sigma: f32) -> Result<Self, Error> {
+        check_non_negative("min_sigma", min_sigma)?;
+
+        if initial_sigma.is_nan() || initial_sigma < min_sigma {
+            return Err(Error::InvalidParameter {
+                name: "initial_sigma",
+                value: initial_sigma,
+            });
+        }
+
+        Ok(Self {
+            initial_sigma,
+            min_sigma,
+        })
+    }
+
+    /// Appends initial step sizes to a chromosome, one for each gene.
+    pub fn with_step_sizes(&self, chromosome: Chromosome) -> Chromosome {
+        let len = chromosome.len();
+
+        chromosome
+            .into_iter()
+            .chain(vec![self.initial_sigma; len])
+            .collect()
+    }
+}
+
+impl MutationMethod for SelfAdaptiveMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        self.try_mutate(rng, child)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+}
+
+impl SelfAdaptiveMutation {
+    /// Same as `mutate()`, but - instead of panicking - returns an error
+    /// when given chromosome can't carry step sizes (i.e. its length is
+    /// odd).
+    pub fn try_mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) -> Result<(), Error> {
+        if child.len() % 2 != 0 {
+            return Err(Error::MissingStepSizes);
+        }
+
+        let len = child.len() / 2;
+
+        if len == 0 {
+            return Ok(());
+        }
+
+        // Learning rates, as recommended by Schwefel
+        let tau = 1.0 / (2.0 * (len as f32).sqrt()).sqrt();
+        let tau_prime = 1.0 / (2.0 * len as f32).sqrt();
+
+        let global = tau_prime * rng.sample::<f32, _>(StandardNormal);
+        let (genes, sigmas) = child.genes.split_at_mut(len);
+
+        for (gene, sigma) in genes.iter_mut().zip(sigmas) {
+            let local = tau * rng.sample::<f32, _>(StandardNormal);
+
+            *sigma = (*sigma * (global + local).exp()).max(self.min_sigma);
+            *gene += *sigma * rng.sample::<f32, _>(StandardNormal);
+        }
+
+        Ok(())
+    }
+}
+
+fn check_chance(chance: f32) -> Result<(), Error> {
+    if (0.0..=1.0).contains(&chance) {
+        Ok(())
+    } else {
+        Err(Error::InvalidChance(chance))
+    }
+}
+
+/// (NaNs are rejected, too.)
+fn check_non_negative(name: &'static str, value: f32) -> Result<(), Error> {
+    if value >= 0.0 {
+        Ok(())
+    } else {
+        Err(Error::InvalidParameter { name, value })
+    }
+}
+
+impl<S> GeneticAlgorithm<S>
+where
+    S: SelectionMethod
+{
+pub fn new(
+        selection_method: S,
+        crossover_method: impl CrossoverMethod + 'static,
+        mutation_method: impl MutationMethod + 'static,
+    ) -> Self {
+        Self {
+            selection_method,
+            crossover_method: Box::new(crossover_method),
+            mutation_method: Box::new(mutation_method),
+            elite_count: 0,
+        }
+    }
+
+    /// Makes `evolve()` carry the `elite_count` fittest individuals
+    /// over to the next population as they are, so that the best
+    /// solution found so far can never get lost.
+    pub fn with_elitism(mut self, elite_count: usize) -> Self {
+        self.elite_count = elite_count;
+        self
+    }
+
+    pub fn evolve<I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &[I],
+    ) -> (Vec<I>, Statistics)
+        where I: Individual
+    {
+        self.evolve_generation(rng, population, 0)
+    }
+
+    /// Same as `evolve()`, but - instead of panicking - returns an error
+    /// when parents can't be selected (see `SelectionMethod::try_select()`).
+    pub fn try_evolve<I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &[I],
+    ) -> Result<(Vec<I>, Statistics), Error>
+        where I: Individual
+    {
+        self.try_evolve_generation(rng, population, 0)
+    }
+
+    /// Like `evolve()`, but lets generation-aware mutation methods know
+    /// which generation is being created.
+    pub fn evolve_generation<I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &[I],
+        generation: usize,
+    ) -> (Vec<I>, Statistics)
+        where I: Individual
+    {
+        self.try_evolve_generation(rng, population, generation)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as `evolve_generation()`, but - instead of panicking -
+    /// returns an error when parents can't be selected.
+    pub fn try_evolve_generation<I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &[I],
+        generation: usize,
+    ) -> Result<(Vec<I>, Statistics), Error>
+        where I: Individual
+    {
+        if population.is_empty() {
+            return Err(Error::EmptyPopulation);
+        }
+
+        let elite_count = self.elite_count.min(population.len());
+        let sorted = try_sorted_by_fitness(population)?;
+
+        let elites = sorted
+            .iter()
+            .rev()
+            .take(elite_count)
+            .map(|individual| I::create(individual.chromosome().clone()));
+
+        let mut new_population: Vec<_> = elites.collect();
+
+        for _ in elite_count..population.len() {
+            let parent_a = self
+                .selection_method
+                .try_select_presorted(rng, population, &sorted)?
+                .chromosome();
+
+            let parent_b = self
+                .selection_method
+                .try_select_presorted(rng, population, &sorted)?
+                .chromosome();
+
+            new_population.push(I::create(self.offspring(rng, parent_a, parent_b, generation)));
+        }
+
+        let stats = Statistics::new(population);
+
+        Ok((new_population, stats))
+    }
+
+    /// Creates a single child by crossing given parents over and then
+    /// mutating the result - for populations that reproduce
+    /// continuously instead of generation by generation.
+    ///
+    /// Passing the same chromosome as both parents yields an asexual
+    /// offspring.
+    pub fn offspring(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+        generation: usize,
+    ) -> Chromosome {
+        let mut child = self
+            .crossover_method
+            .crossover(rng, parent_a, parent_b);
+
+        self.mutation_method
+            .mutate_in_generation(rng, &mut child, generation);
+
+        child
     }
 }
 
-#[derive(Clone, Debug)]
-pub struct GaussianMutation {
-    /// Probability of changing a gene:
-    /// - 0.0 = no genes will be touched
-    /// - 1.0 = all genes will be touched
-    chance: f32,
-
-    /// Magnitude of that change:
-    /// - 0.0 = touched genes will not be modified
-    /// - 3.0 = touched genes will be += or -= by at most 3.0
-    coeff: f32,
+/// Summary of the fitness of a population that has been evolved.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Statistics {
+    min_fitness: f32,
+    max_fitness: f32,
+    mean_fitness: f32,
+    median_fitness: f32,
+    std_dev_fitness: f32,
 }
 
-impl GaussianMutation {
-    pub fn new(chance: f32, coeff: f32) -> Self {
-        assert!(chance >= 0.0 && chance <= 1.0);
-
-        Self { chance, coeff }
+impl Statistics {
+    pub fn new<I>(population: &[I]) -> Self
+    where
+        I: Individual,
+    {
+        Self::from_fitnesses(
+            population
+                .iter()
+                .map(|individual| individual.fitness())
+                .collect(),
+        )
     }
-}
 
-impl MutationMethod for GaussianMutation {
-    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
-        for gene in child.iter_mut() {
-            let sign = if rng.gen_bool(0.5) { -1.0 } else { 1.0 };
+    pub(crate) fn from_fitnesses(mut fitnesses: Vec<f32>) -> Self {
+        assert!(!fitnesses.is_empty());
 
-            if rng.gen_bool(self.chance as _) {
-                *gene += sign * self.coeff * rng.gen::<f32>();
-            }
-        }
-    }
-}
+        fitnesses.sort_by(|a, b| a.partial_cmp(b).expect("got a NaN fitness"));
+
+        let len = fitnesses.len();
+        let mean_fitness = fitnesses.iter().sum::<f32>() / len as f32;
+
+        let median_fitness = if len % 2 == 0 {
+            (fitnesses[len / 2 - 1] + fitnesses[len / 2]) / 2.0
+        } else {
+            fitnesses[len / 2]
+        };
+
+        let variance = fitnesses
+            .iter()
+            .map(|fitness| (fitness - mean_fitness).powi(2))
+            .sum::<f32>()
+            / len as f32;
 
-impl<S> GeneticAlgorithm<S>
-where
-    S: SelectionMethod
-{
-pub fn new(
-        selection_method: S,
-        crossover_method: impl CrossoverMethod + 'static,
-        mutation_method: impl MutationMethod + 'static,
-    ) -> Self {
         Self {
-            selection_method,
-            crossover_method: Box::new(crossover_method),
-            mutation_method: Box::new(mutation_method),
+            min_fitness: fitnesses[0],
+            max_fitness: fitnesses[len - 1],
+            mean_fitness,
+            median_fitness,
+            std_dev_fitness: variance.sqrt(),
         }
     }
-    pub fn evolve<I>(
-        &self,
-        rng: &mut dyn RngCore,
-        population: &[I],
-    ) -> Vec<I>
-        where I: Individual
-    {
-        assert!(!population.is_empty());
-        (0..population.len())
-            .map(|_| {
-                let parent_a = self
-                    .selection_method
-                    .select(rng, &population)
-                    .chromosome();
-
-                let parent_b = self
-                    .selection_method
-                    .select(rng, &population)
-                    .chromosome();
-
-                let mut child = self
-                    .crossover_method
-                    .crossover(rng, parent_a, parent_b);
-
-                self.mutation_method.mutate(rng, &mut child);
-
-                I::create(child)
-            })
-            .collect()
+
+    pub fn min_fitness(&self) -> f32 {
+        self.min_fitness
+    }
+
+    pub fn max_fitness(&self) -> f32 {
+        self.max_fitness
+    }
+
+    pub fn mean_fitness(&self) -> f32 {
+        self.mean_fitness
+    }
+
+    pub fn median_fitness(&self) -> f32 {
+        self.median_fitness
+    }
+
+    pub fn std_dev_fitness(&self) -> f32 {
+        self.std_dev_fitness
     }
 }
 
@@ -312,6 +1114,208 @@ mod tests {
             assert_eq!(actual_histogram, expected_histogram);
         }
 
+        #[test]
+        fn given_empty_population() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let population: Vec<TestIndividual> = Vec::new();
+
+            let actual = RouletteWheelSelection::new().try_select(&mut rng, &population);
+
+            assert_eq!(actual.err(), Some(Error::EmptyPopulation));
+        }
+
+        #[test]
+        fn given_zero_fitnesses() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let population = vec![TestIndividual::new(0.0); 4];
+
+            let actual = RouletteWheelSelection::new().try_select(&mut rng, &population);
+
+            assert_eq!(actual.err(), Some(Error::ZeroFitness));
+        }
+    }
+
+    /// Runs given selection method a thousand times over a population
+    /// of fitnesses 1, 2, 3 & 4, returning how many times each fitness
+    /// has been chosen
+    fn selection_histogram(method: &impl SelectionMethod) -> BTreeMap<i32, i32> {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let population = vec![
+            TestIndividual::new(2.0),
+            TestIndividual::new(1.0),
+            TestIndividual::new(4.0),
+            TestIndividual::new(3.0),
+        ];
+
+        let mut histogram = BTreeMap::new();
+
+        for _ in 0..1000 {
+            let fitness = method
+                .select(&mut rng, &population)
+                .fitness() as i32;
+
+            *histogram
+                .entry(fitness)
+                .or_insert(0) += 1;
+        }
+
+        histogram
+    }
+
+    /// Selection methods other than the roulette wheel must cope with a
+    /// population where nobody has scored anything yet
+    fn select_from_zero_fitnesses(method: &impl SelectionMethod) {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let population = vec![TestIndividual::new(0.0); 4];
+
+        approx::assert_relative_eq!(method.select(&mut rng, &population).fitness(), 0.0);
+    }
+
+    /// Picking from a presorted population must go the same way as
+    /// picking from the unsorted one
+    fn select_presorted(method: &impl SelectionMethod) {
+        let population: Vec<_> = [3.0, 1.0, 4.0, 2.0, 5.0]
+            .iter()
+            .map(|&fitness| TestIndividual::new(fitness))
+            .collect();
+
+        let sorted = try_sorted_by_fitness(&population).unwrap();
+
+        for seed in 0..10 {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let expected = method.try_select(&mut rng, &population).unwrap();
+
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let actual = method.try_select_presorted(&mut rng, &population, &sorted).unwrap();
+
+            assert_eq!(actual, expected);
+        }
+    }
+
+    /// ... but none of them can pick from an empty population or weigh
+    /// a NaN fitness
+    fn select_from_invalid_populations(method: &impl SelectionMethod) {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let empty: Vec<TestIndividual> = Vec::new();
+        let nan = vec![TestIndividual::new(f32::NAN); 4];
+
+        assert_eq!(method.try_select(&mut rng, &empty).err(), Some(Error::EmptyPopulation));
+        assert_eq!(method.try_select(&mut rng, &nan).err(), Some(Error::InvalidFitness));
+    }
+
+    mod tournament_selection {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let actual_histogram = selection_histogram(&TournamentSelection::new(2));
+
+            let expected_histogram = BTreeMap::from_iter(vec![
+                // (fitness, how many times this fitness has been chosen)
+                (1, 66),  // 'near' 62.5 (1/16 of tournaments)
+                (2, 179), // 'near' 187.5 (3/16)
+                (3, 299), // 'near' 312.5 (5/16)
+                (4, 456), // 'near' 437.5 (7/16)
+            ]);
+
+            assert_eq!(actual_histogram, expected_histogram);
+        }
+
+        #[test]
+        fn given_zero_fitnesses() {
+            select_from_zero_fitnesses(&TournamentSelection::new(2));
+        }
+
+        #[test]
+        fn given_invalid_population() {
+            select_from_invalid_populations(&TournamentSelection::new(2));
+        }
+
+        #[test]
+        fn try_new() {
+            assert!(TournamentSelection::try_new(1).is_ok());
+
+            assert_eq!(
+                TournamentSelection::try_new(0).err(),
+                Some(Error::InvalidTournamentSize),
+            );
+        }
+    }
+
+    mod rank_selection {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let actual_histogram = selection_histogram(&RankSelection::new());
+
+            let expected_histogram = BTreeMap::from_iter(vec![
+                // (fitness, how many times this fitness has been chosen)
+                (1, 95),  // 'near' 100 (rank 1 out of 1 + 2 + 3 + 4)
+                (2, 194), // 'near' 200
+                (3, 310), // 'near' 300
+                (4, 401), // 'near' 400
+            ]);
+
+            assert_eq!(actual_histogram, expected_histogram);
+        }
+
+        #[test]
+        fn given_zero_fitnesses() {
+            select_from_zero_fitnesses(&RankSelection::new());
+        }
+
+        #[test]
+        fn given_invalid_population() {
+            select_from_invalid_populations(&RankSelection::new());
+        }
+
+        #[test]
+        fn presorted() {
+            select_presorted(&RankSelection::new());
+        }
+    }
+
+    mod truncation_selection {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let actual_histogram = selection_histogram(&TruncationSelection::new(0.5));
+
+            let expected_histogram = BTreeMap::from_iter(vec![
+                // (fitness, how many times this fitness has been chosen)
+                (3, 479), // 'near' 500 - only the top half
+                (4, 521), // 'near' 500   is ever chosen
+            ]);
+
+            assert_eq!(actual_histogram, expected_histogram);
+        }
+
+        #[test]
+        fn given_zero_fitnesses() {
+            select_from_zero_fitnesses(&TruncationSelection::new(0.5));
+        }
+
+        #[test]
+        fn given_invalid_population() {
+            select_from_invalid_populations(&TruncationSelection::new(0.5));
+        }
+
+        #[test]
+        fn presorted() {
+            select_presorted(&TruncationSelection::new(0.5));
+        }
+
+        #[test]
+        fn try_new() {
+            let actual = |fraction| TruncationSelection::try_new(fraction).err();
+
+            assert_eq!(actual(1.0), None);
+            assert_eq!(actual(0.0), Some(Error::InvalidFraction(0.0)));
+            assert_eq!(actual(1.5), Some(Error::InvalidFraction(1.5)));
+        }
     }
 
     mod chromosome {
@@ -415,43 +1419,189 @@ mod tests {
             }
         }
 
-    }
+    }
+
+    mod crossover {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let parent_a: Chromosome =
+                (1..=100)
+                    .map(|n| n as f32)
+                    .collect();
+
+            let parent_b: Chromosome =
+                (1..=100)
+                    .map(|n| -n as f32)
+                    .collect();
+
+            let child = UniformCrossover::new()
+                .crossover(&mut rng, &parent_a, &parent_b);
+
+            // Number of genes different between `child` and `parent_a`
+            let diff_a = child
+                .iter()
+                .zip(parent_a)
+                .filter(|(c, p)| *c != p)
+                .count();
+
+            // Number of genes different between `child` and `parent_b`
+            let diff_b = child
+                .iter()
+                .zip(parent_b)
+                .filter(|(c, p)| *c != p)
+                .count();
+
+            assert_eq!(diff_a, 49);
+            assert_eq!(diff_b, 51);
+        }
+    }
+
+    mod crossover_methods {
+        use super::*;
+
+        fn parents() -> (Chromosome, Chromosome) {
+            let parent_a = (1..=10).map(|n| n as f32).collect();
+            let parent_b = (1..=10).map(|n| -n as f32).collect();
+
+            (parent_a, parent_b)
+        }
+
+        fn actual(method: &dyn CrossoverMethod) -> Vec<f32> {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let (parent_a, parent_b) = parents();
+
+            method
+                .crossover(&mut rng, &parent_a, &parent_b)
+                .into_iter()
+                .collect()
+        }
+
+        /// Number of places where the child switches from one parent to
+        /// the other
+        fn switches(child: &[f32]) -> usize {
+            child
+                .windows(2)
+                .filter(|genes| genes[0].signum() != genes[1].signum())
+                .count()
+        }
+
+        mod single_point {
+            use super::*;
+
+            #[test]
+            fn test() {
+                let actual = actual(&SinglePointCrossover::new());
+                let expected = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, -9.0, -10.0];
+
+                assert_eq!(switches(&actual), 1);
+                approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+            }
+        }
+
+        mod k_point {
+            use super::*;
+
+            #[test]
+            fn test() {
+                let actual = actual(&KPointCrossover::new(3));
+                let expected = vec![1.0, 2.0, -3.0, 4.0, 5.0, 6.0, 7.0, 8.0, -9.0, -10.0];
+
+                assert_eq!(switches(&actual), 3);
+                approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+            }
+
+            #[test]
+            fn given_more_points_than_genes() {
+                let actual = actual(&KPointCrossover::new(100));
+                let expected = vec![1.0, -2.0, 3.0, -4.0, 5.0, -6.0, 7.0, -8.0, 9.0, -10.0];
+
+                approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+            }
+
+            #[test]
+            fn try_new() {
+                assert!(KPointCrossover::try_new(1).is_ok());
+                assert_eq!(KPointCrossover::try_new(0).err(), Some(Error::InvalidPointCount));
+            }
+        }
+
+        mod arithmetic {
+            use super::*;
+
+            #[test]
+            fn given_zero_alpha_stays_between_parents() {
+                let actual = actual(&ArithmeticCrossover::new(0.0));
+
+                for (gene, n) in actual.iter().zip(1..) {
+                    assert!(gene.abs() <= n as f32);
+                }
+            }
+
+            #[test]
+            fn test() {
+                let actual = actual(&ArithmeticCrossover::new(0.5));
+                let expected = vec![
+                    -1.2510376, 2.6953583, 4.908757, 2.1027918, 5.2388067,
+                    -6.42202, 0.97117615, -12.237091, -1.8449898, -9.775924,
+                ];
+
+                approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+            }
+
+            #[test]
+            fn try_new() {
+                assert!(ArithmeticCrossover::try_new(0.0).is_ok());
+
+                assert_eq!(
+                    ArithmeticCrossover::try_new(-0.5).err(),
+                    Some(Error::InvalidParameter { name: "alpha", value: -0.5 }),
+                );
+            }
+        }
+
+        mod simulated_binary {
+            use super::*;
 
-    mod crossover {
-        use super::*;
+            #[test]
+            fn test() {
+                let actual = actual(&SimulatedBinaryCrossover::new(2.0));
+                let expected = vec![
+                    -0.72079206, 2.2140012, 3.0727615, 3.1983404, -5.5669327,
+                    4.835296, 6.6320076, 9.156639, 6.8696437, -9.245741,
+                ];
 
-        #[test]
-        fn test() {
-            let mut rng = ChaCha8Rng::from_seed(Default::default());
-            let parent_a: Chromosome =
-                (1..=100)
-                    .map(|n| n as f32)
-                    .collect();
+                approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+            }
 
-            let parent_b: Chromosome =
-                (1..=100)
-                    .map(|n| -n as f32)
+            #[test]
+            fn given_identical_parents_yields_the_same_genes() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let (parent, _) = parents();
+
+                let child: Vec<_> = SimulatedBinaryCrossover::new(2.0)
+                    .crossover(&mut rng, &parent, &parent)
+                    .into_iter()
                     .collect();
 
-            let child = UniformCrossover::new()
-                .crossover(&mut rng, &parent_a, &parent_b);
+                let parent: Vec<_> = parent.into_iter().collect();
 
-            // Number of genes different between `child` and `parent_a`
-            let diff_a = child
-                .iter()
-                .zip(parent_a)
-                .filter(|(c, p)| *c != p)
-                .count();
+                approx::assert_relative_eq!(child.as_slice(), parent.as_slice(), epsilon = 1e-5);
+            }
 
-            // Number of genes different between `child` and `parent_b`
-            let diff_b = child
-                .iter()
-                .zip(parent_b)
-                .filter(|(c, p)| *c != p)
-                .count();
+            #[test]
+            fn try_new() {
+                assert!(SimulatedBinaryCrossover::try_new(2.0).is_ok());
 
-            assert_eq!(diff_a, 49);
-            assert_eq!(diff_b, 51);
+                assert_eq!(
+                    SimulatedBinaryCrossover::try_new(-1.0).err(),
+                    Some(Error::InvalidParameter { name: "eta", value: -1.0 }),
+                );
+
+                assert!(SimulatedBinaryCrossover::try_new(f32::NAN).is_err());
+            }
         }
     }
 
@@ -471,6 +1621,19 @@ mod tests {
             child.into_iter().collect()
         }
 
+        mod try_new {
+            use super::*;
+
+            #[test]
+            fn test() {
+                let actual = |chance| GaussianMutation::try_new(chance, 2.0).err();
+
+                assert_eq!(actual(0.5), None);
+                assert_eq!(actual(1.5), Some(Error::InvalidChance(1.5)));
+                assert_eq!(actual(-0.1), Some(Error::InvalidChance(-0.1)));
+            }
+        }
+
         mod given_zero_chance {
             fn actual(coeff: f32) -> Vec<f32> {
                 super::actual_mutation_child(0.0, coeff)
@@ -587,6 +1750,244 @@ mod tests {
         }
     }
 
+    mod normal_mutation {
+        use super::*;
+
+        fn actual(chance: f32, sigma: f32) -> Vec<f32> {
+            let mut child = vec![1.0, 2.0, 3.0, 4.0, 5.0]
+                .into_iter()
+                .collect();
+
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            NormalMutation::new(chance, sigma)
+                .mutate(&mut rng, &mut child);
+
+            child.into_iter().collect()
+        }
+
+        #[test]
+        fn given_zero_chance_does_not_change_the_original_chromosome() {
+            let actual = actual(0.0, 0.5);
+            let expected = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+
+            approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+        }
+
+        #[test]
+        fn given_zero_sigma_does_not_change_the_original_chromosome() {
+            let actual = actual(1.0, 0.0);
+            let expected = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+
+            approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+        }
+
+        #[test]
+        fn given_max_chance_entirely_changes_the_original_chromosome() {
+            let actual = actual(1.0, 0.5);
+            let expected = vec![1.6888486, 2.2026734, 2.4018655, 3.0324764, 4.664113];
+
+            approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+        }
+
+        #[test]
+        fn is_normally_distributed() {
+            let mut child: Chromosome = vec![0.0; 10_000].into_iter().collect();
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            NormalMutation::new(1.0, 0.5).mutate(&mut rng, &mut child);
+
+            let len = child.len() as f32;
+            let mean = child.iter().sum::<f32>() / len;
+            let std_dev = (child.iter().map(|gene| (gene - mean).powi(2)).sum::<f32>() / len).sqrt();
+
+            // For a uniform perturbation, ~100% of the genes would lie
+            // within 1.73 std devs; for a normal one, that's only ~92%
+            let within = child
+                .iter()
+                .filter(|gene| (*gene - mean).abs() <= 1.73 * std_dev)
+                .count() as f32
+                / len;
+
+            approx::assert_relative_eq!(mean, 0.0, epsilon = 0.02);
+            approx::assert_relative_eq!(std_dev, 0.5, epsilon = 0.02);
+            approx::assert_relative_eq!(within, 0.92, epsilon = 0.01);
+        }
+
+        #[test]
+        fn try_new() {
+            assert!(NormalMutation::try_new(0.5, 0.5).is_ok());
+            assert_eq!(NormalMutation::try_new(1.5, 0.5).err(), Some(Error::InvalidChance(1.5)));
+
+            assert_eq!(
+                NormalMutation::try_new(0.5, -0.5).err(),
+                Some(Error::InvalidParameter { name: "sigma", value: -0.5 }),
+            );
+        }
+    }
+
+    mod annealed_mutation {
+        use super::*;
+
+        fn actual_changes(generation: usize) -> Vec<f32> {
+            let mut child = vec![1.0, 2.0, 3.0, 4.0, 5.0]
+                .into_iter()
+                .collect();
+
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            AnnealedMutation::new(1.0, 0.5, 0.9)
+                .mutate_in_generation(&mut rng, &mut child, generation);
+
+            child
+                .into_iter()
+                .zip(1..)
+                .map(|(gene, n)| gene - n as f32)
+                .collect()
+        }
+
+        #[test]
+        fn sigma() {
+            let mutation = AnnealedMutation::new(1.0, 0.5, 0.9);
+
+            approx::assert_relative_eq!(mutation.sigma(0), 0.5);
+            approx::assert_relative_eq!(mutation.sigma(1), 0.45);
+            approx::assert_relative_eq!(mutation.sigma(10), 0.17433922);
+        }
+
+        #[test]
+        fn calms_down_with_generations() {
+            let initial: Vec<_> = actual_changes(0)
+                .into_iter()
+                .map(|change| change * 0.9_f32.powi(10))
+                .collect();
+
+            let annealed = actual_changes(10);
+
+            approx::assert_relative_eq!(initial.as_slice(), annealed.as_slice(), epsilon = 1e-6);
+        }
+
+        #[test]
+        fn mutate_is_the_first_generation() {
+            let mut child: Chromosome = vec![1.0, 2.0, 3.0, 4.0, 5.0].into_iter().collect();
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            AnnealedMutation::new(1.0, 0.5, 0.9).mutate(&mut rng, &mut child);
+
+            let actual: Vec<_> = child
+                .into_iter()
+                .zip(1..)
+                .map(|(gene, n)| gene - n as f32)
+                .collect();
+
+            let expected = actual_changes(0);
+
+            approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+        }
+
+        #[test]
+        fn try_new() {
+            assert!(AnnealedMutation::try_new(1.0, 0.5, 0.9).is_ok());
+
+            assert_eq!(
+                AnnealedMutation::try_new(1.0, -0.5, 0.9).err(),
+                Some(Error::InvalidParameter { name: "initial_sigma", value: -0.5 }),
+            );
+
+            assert_eq!(
+                AnnealedMutation::try_new(1.0, 0.5, 1.5).err(),
+                Some(Error::InvalidParameter { name: "decay", value: 1.5 }),
+            );
+        }
+    }
+
+    mod self_adaptive_mutation {
+        use super::*;
+
+        fn chromosome() -> Chromosome {
+            SelfAdaptiveMutation::new(0.5, 0.01)
+                .with_step_sizes(vec![1.0, 2.0, 3.0].into_iter().collect())
+        }
+
+        #[test]
+        fn with_step_sizes() {
+            let actual: Vec<_> = chromosome().into_iter().collect();
+            let expected = vec![1.0, 2.0, 3.0, 0.5, 0.5, 0.5];
+
+            approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+        }
+
+        #[test]
+        fn test() {
+            let mut child = chromosome();
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            SelfAdaptiveMutation::new(0.5, 0.01).mutate(&mut rng, &mut child);
+
+            let actual: Vec<_> = child.into_iter().collect();
+            let expected = vec![
+                // genes:
+                -0.30511296, 1.7915821, 1.9357346,
+                // their step sizes:
+                1.0909863, 0.31025016, 0.49524295,
+            ];
+
+            approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+        }
+
+        #[test]
+        fn respects_min_sigma() {
+            let mutation = SelfAdaptiveMutation::new(0.5, 0.4);
+            let mut child = mutation.with_step_sizes(vec![0.0; 50].into_iter().collect());
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            for _ in 0..20 {
+                mutation.mutate(&mut rng, &mut child);
+            }
+
+            assert!(child.iter().skip(50).all(|&sigma| sigma >= 0.4));
+        }
+
+        #[test]
+        #[should_panic(expected = "got a chromosome without step sizes")]
+        fn given_chromosome_without_step_sizes() {
+            let mut child: Chromosome = vec![1.0, 2.0, 3.0].into_iter().collect();
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            SelfAdaptiveMutation::new(0.5, 0.01).mutate(&mut rng, &mut child);
+        }
+
+        #[test]
+        fn try_mutate() {
+            let mutation = SelfAdaptiveMutation::new(0.5, 0.01);
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let mut child: Chromosome = vec![1.0, 2.0, 3.0].into_iter().collect();
+            assert_eq!(mutation.try_mutate(&mut rng, &mut child), Err(Error::MissingStepSizes));
+
+            // (chromosome is left intact)
+            assert_eq!(child, vec![1.0, 2.0, 3.0].into_iter().collect());
+
+            let mut child = chromosome();
+            assert_eq!(mutation.try_mutate(&mut rng, &mut child), Ok(()));
+        }
+
+        #[test]
+        fn try_new() {
+            assert!(SelfAdaptiveMutation::try_new(0.5, 0.5).is_ok());
+
+            assert_eq!(
+                SelfAdaptiveMutation::try_new(0.5, -0.1).err(),
+                Some(Error::InvalidParameter { name: "min_sigma", value: -0.1 }),
+            );
+
+            assert_eq!(
+                SelfAdaptiveMutation::try_new(0.1, 0.5).err(),
+                Some(Error::InvalidParameter { name: "initial_sigma", value: 0.1 }),
+            );
+        }
+    }
+
     mod evolve {
         use super::*;
 
@@ -621,7 +2022,7 @@ mod tests {
             // that'd change is the *magnitude* of difference between
             // initial and output population.
             for _ in 0..10 {
-                population = ga.evolve(&mut rng, &population);
+                population = ga.evolve(&mut rng, &population).0;
             }
 
             let expected_population = vec![
@@ -633,6 +2034,136 @@ mod tests {
 
             assert_eq!(population, expected_population);
         }
+
+        #[test]
+        fn with_elitism_keeps_the_fittest_chromosomes() {
+            fn individual(genes: &[f32]) -> TestIndividual {
+                let chromosome = genes.iter().cloned().collect();
+                TestIndividual::create(chromosome)
+            }
+
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let ga = GeneticAlgorithm::new(
+                RouletteWheelSelection::new(),
+                UniformCrossover::new(),
+                GaussianMutation::new(1.0, 0.5),
+            )
+            .with_elitism(2);
+
+            let population = vec![
+                individual(&[0.0, 0.0, 0.0]), // fitness = 0.0
+                individual(&[1.0, 2.0, 4.0]), // fitness = 7.0
+                individual(&[1.0, 1.0, 1.0]), // fitness = 3.0
+                individual(&[1.0, 2.0, 1.0]), // fitness = 4.0
+            ];
+
+            let (new_population, _) = ga.evolve(&mut rng, &population);
+
+            assert_eq!(new_population.len(), population.len());
+            assert_eq!(new_population[0], population[1]);
+            assert_eq!(new_population[1], population[3]);
+            assert!(!new_population[2..].contains(&population[1]));
+        }
+
+        #[test]
+        fn try_evolve() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let ga = GeneticAlgorithm::new(
+                RouletteWheelSelection::new(),
+                UniformCrossover::new(),
+                GaussianMutation::new(0.5, 0.5),
+            );
+
+            let empty: Vec<TestIndividual> = Vec::new();
+            let zero = vec![TestIndividual::new(0.0); 4];
+
+            assert_eq!(ga.try_evolve(&mut rng, &empty).err(), Some(Error::EmptyPopulation));
+            assert_eq!(ga.try_evolve(&mut rng, &zero).err(), Some(Error::ZeroFitness));
+        }
+    }
+
+    mod offspring {
+        use super::*;
+
+        #[test]
+        fn given_same_parent_twice_without_mutation_yields_a_clone() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let ga = GeneticAlgorithm::new(
+                RouletteWheelSelection::new(),
+                UniformCrossover::new(),
+                GaussianMutation::new(0.0, 0.0),
+            );
+
+            let parent: Chromosome = vec![1.0, 2.0, 3.0].into_iter().collect();
+            let child = ga.offspring(&mut rng, &parent, &parent, 0);
+
+            assert_eq!(child, parent);
+        }
+
+        #[test]
+        fn mixes_and_mutates_parents() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let ga = GeneticAlgorithm::new(
+                RouletteWheelSelection::new(),
+                UniformCrossover::new(),
+                GaussianMutation::new(0.5, 0.5),
+            );
+
+            let parent_a: Chromosome = vec![1.0, 1.0, 1.0, 1.0].into_iter().collect();
+            let parent_b: Chromosome = vec![-1.0, -1.0, -1.0, -1.0].into_iter().collect();
+
+            let actual: Vec<_> = ga
+                .offspring(&mut rng, &parent_a, &parent_b, 0)
+                .into_iter()
+                .collect();
+
+            let expected = vec![-1.3188651, -0.8403195, 1.0, 1.0];
+
+            approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+        }
+    }
+
+    mod statistics {
+        use super::*;
+
+        #[test]
+        fn odd_population() {
+            let population = vec![
+                TestIndividual::new(30.0),
+                TestIndividual::new(10.0),
+                TestIndividual::new(20.0),
+            ];
+
+            let stats = Statistics::new(&population);
+
+            approx::assert_relative_eq!(stats.min_fitness(), 10.0);
+            approx::assert_relative_eq!(stats.max_fitness(), 30.0);
+            approx::assert_relative_eq!(stats.mean_fitness(), 20.0);
+            approx::assert_relative_eq!(stats.median_fitness(), 20.0);
+            approx::assert_relative_eq!(stats.std_dev_fitness(), 8.164966);
+        }
+
+        #[test]
+        fn even_population() {
+            let population = vec![
+                TestIndividual::new(40.0),
+                TestIndividual::new(10.0),
+                TestIndividual::new(20.0),
+                TestIndividual::new(10.0),
+            ];
+
+            let stats = Statistics::new(&population);
+
+            approx::assert_relative_eq!(stats.min_fitness(), 10.0);
+            approx::assert_relative_eq!(stats.max_fitness(), 40.0);
+            approx::assert_relative_eq!(stats.mean_fitness(), 20.0);
+            approx::assert_relative_eq!(stats.median_fitness(), 15.0);
+            approx::assert_relative_eq!(stats.std_dev_fitness(), 12.247449);
+        }
     }
 }
 
@@ -0,0 +1,1089 @@
+//! NEAT - NeuroEvolution of Augmenting Topologies.
+//!
+//! Instead of evolving just the weights of a network whose shape is
+//! fixed upfront, NEAT starts with the simplest network possible (each
+//! input connected straight to each output) and lets the evolution grow
+//! it, node by node and connection by connection.
+//!
+//! Networks are described by `Genome`s; turning them into something that
+//! can actually be propagated is up to the caller (e.g. see
+//! `lib_neural_network::GraphNetwork`).
+//!
+//! See: http://nn.cs.utexas.edu/downloads/papers/stanley.ec02.pdf
+
+use crate::*;
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NodeKind {
+    Input,
+    Output,
+    Hidden,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NodeGene {
+    pub id: usize,
+    pub kind: NodeKind,
+
+    /// (ignored for inputs)
+    pub bias: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConnectionGene {
+    /// Historical marking - connections with the same innovation number
+    /// have been created by the same structural mutation, so they're
+    /// the ones that get lined up during crossover
+    pub innovation: usize,
+    pub from: usize,
+    pub to: usize,
+    pub weight: f32,
+
+    /// Disabled connections don't take part in propagation, but they're
+    /// still inherited (and can get re-enabled by crossover)
+    pub enabled: bool,
+}
+
+/// Genome of a feed-forward network of an arbitrary shape.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Genome {
+    /// Sorted by id; inputs go first, then outputs, then hidden nodes
+    nodes: Vec<NodeGene>,
+
+    /// Sorted by innovation number
+    connections: Vec<ConnectionGene>,
+}
+
+pub trait NeatIndividual {
+    fn create(genome: Genome) -> Self;
+    fn genome(&self) -> &Genome;
+    fn fitness(&self) -> f32;
+}
+
+/// Keeps track of the structural mutations that have happened so far, so
+/// that the same mutation happening in two different genomes yields
+/// the same innovation numbers.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Innovations {
+    next_node: usize,
+    next_innovation: usize,
+
+    /// from -> to -> innovation number of the connection between them
+    connections: BTreeMap<usize, BTreeMap<usize, usize>>,
+
+    /// innovation number of a split connection -> node it's been split
+    /// with
+    splits: BTreeMap<usize, usize>,
+}
+
+/// Coefficients of `Genome::compatibility()`
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Compatibility {
+    pub excess_coeff: f32,
+    pub disjoint_coeff: f32,
+    pub weight_coeff: f32,
+
+    /// Genomes closer than this belong to the same species
+    pub threshold: f32,
+}
+
+/// Drives NEAT's evolution: speciation, fitness sharing, crossover and
+/// (both weight and structural) mutation.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Neat {
+    innovations: Innovations,
+    compatibility: Compatibility,
+
+    /// Probability of perturbing each weight & bias
+    weight_mut_chance: f32,
+    /// Magnitude of that perturbation
+    weight_mut_coeff: f32,
+
+    /// Probability of splitting a connection with a new node
+    add_node_chance: f32,
+    /// Probability of connecting two so-far unconnected nodes
+    add_connection_chance: f32,
+
+    /// Part of each species (the fittest one) that gets to reproduce
+    survival_fraction: f32,
+}
+
+impl Genome {
+    /// Creates a genome with each input connected to each output.
+    ///
+    /// Node ids and innovation numbers of those connections are the same
+    /// for all genomes of given shape - see `Innovations::new()`.
+    pub fn minimal(rng: &mut dyn RngCore, inputs: usize, outputs: usize) -> Self {
+        assert!(inputs > 0);
+        assert!(outputs > 0);
+
+        let nodes = (0..inputs)
+            .map(|id| NodeGene {
+                id,
+                kind: NodeKind::Input,
+                bias: 0.0,
+            })
+            .chain((inputs..inputs + outputs).map(|id| NodeGene {
+                id,
+                kind: NodeKind::Output,
+                bias: rng.gen_range(-1.0..=1.0),
+            }))
+            .collect();
+
+        let connections = (0..inputs)
+            .flat_map(|from| (0..outputs).map(move |to| (from, to)))
+            .map(|(from, to)| ConnectionGene {
+                innovation: from * outputs + to,
+                from,
+                to: inputs + to,
+                weight: rng.gen_range(-1.0..=1.0),
+                enabled: true,
+            })
+            .collect();
+
+        Self { nodes, connections }
+    }
+
+    pub fn nodes(&self) -> &[NodeGene] {
+        &self.nodes
+    }
+
+    pub fn connections(&self) -> &[ConnectionGene] {
+        &self.connections
+    }
+
+    /// Weights of all the connections (including the disabled ones),
+    /// ordered by innovation number.
+    pub fn weights(&self) -> impl Iterator<Item = f32> + '_ {
+        self.connections.iter().map(|connection| connection.weight)
+    }
+
+    /// How much two genomes differ: the more non-matching genes and the
+    /// more different the weights of the matching ones, the larger the
+    /// distance.
+    pub fn compatibility(&self, other: &Self, coeffs: &Compatibility) -> f32 {
+        let (mut matching, mut disjoint, mut weight_diff) = (0, 0, 0.0);
+
+        let last_a = self.connections.last().map_or(0, |c| c.innovation);
+        let last_b = other.connections.last().map_or(0, |c| c.innovation);
+        let last_common = last_a.min(last_b);
+
+        let mut excess = 0;
+
+        for connection in &self.connections {
+            match other.connection(connection.innovation) {
+                Some(theirs) => {
+                    matching += 1;
+                    weight_diff += (connection.weight - theirs.weight).abs();
+                }
+                None if connection.innovation > last_common => excess += 1,
+                None => disjoint += 1,
+            }
+        }
+
+        for connection in &other.connections {
+            if self.connection(connection.innovation).is_none() {
+                if connection.innovation > last_common {
+                    excess += 1;
+                } else {
+                    disjoint += 1;
+                }
+            }
+        }
+
+        // As suggested by the paper, small genomes don't get normalized
+        let len = self.connections.len().max(other.connections.len());
+        let len = if len < 20 { 1.0 } else { len as f32 };
+
+        let weight_diff = if matching > 0 {
+            weight_diff / matching as f32
+        } else {
+            0.0
+        };
+
+        coeffs.excess_coeff * excess as f32 / len
+            + coeffs.disjoint_coeff * disjoint as f32 / len
+            + coeffs.weight_coeff * weight_diff
+    }
+
+    /// Combines two genomes: matching genes get inherited from a random
+    /// parent, while the disjoint & excess ones come from the fitter
+    /// parent (`fitter`) only.
+    pub fn crossover(rng: &mut dyn RngCore, fitter: &Self, other: &Self) -> Self {
+        let nodes = fitter
+            .nodes
+            .iter()
+            .map(|node| match other.node(node.id) {
+                Some(theirs) if rng.gen_bool(0.5) => theirs.clone(),
+                _ => node.clone(),
+            })
+            .collect();
+
+        let connections = fitter
+            .connections
+            .iter()
+            .map(|connection| match other.connection(connection.innovation) {
+                Some(theirs) if rng.gen_bool(0.5) => theirs.clone(),
+                _ => connection.clone(),
+            })
+            .collect();
+
+        Self { nodes, connections }
+    }
+
+    fn node(&self, id: usize) -> Option<&NodeGene> {
+        self.nodes
+            .binary_search_by_key(&id, |node| node.id)
+            .ok()
+            .map(|idx| &self.nodes[idx])
+    }
+
+    fn connection(&self, innovation: usize) -> Option<&ConnectionGene> {
+        self.connections
+            .binary_search_by_key(&innovation, |connection| connection.innovation)
+            .ok()
+            .map(|idx| &self.connections[idx])
+    }
+
+    fn mutate_weights(&mut self, rng: &mut dyn RngCore, chance: f32, coeff: f32) {
+        let weights = self
+            .connections
+            .iter_mut()
+            .map(|connection| &mut connection.weight)
+            .chain(
+                self.nodes
+                    .iter_mut()
+                    .filter(|node| node.kind != NodeKind::Input)
+                    .map(|node| &mut node.bias),
+            );
+
+        for weight in weights {
+            let sign = if rng.gen_bool(0.5) { -1.0 } else { 1.0 };
+
+            if rng.gen_bool(chance as _) {
+                *weight += sign * coeff * rng.gen::<f32>();
+            }
+        }
+    }
+
+    /// Connects two random, so-far unconnected nodes - unless that'd
+    /// create a cycle (or there's no such pair to be found).
+    fn mutate_add_connection(&mut self, rng: &mut dyn RngCore, innovations: &mut Innovations) {
+        const ATTEMPTS: usize = 20;
+
+        for _ in 0..ATTEMPTS {
+            let from = self.nodes.choose(rng).unwrap();
+            let to = self.nodes.choose(rng).unwrap();
+
+            if from.kind == NodeKind::Output
+                || to.kind == NodeKind::Input
+                || from.id == to.id
+                || self.connects(from.id, to.id)
+                || self.leads_to(to.id, from.id)
+            {
+                continue;
+            }
+
+            let (from, to) = (from.id, to.id);
+
+            self.push_connection(ConnectionGene {
+                innovation: innovations.connection(from, to),
+                from,
+                to,
+                weight: rng.gen_range(-1.0..=1.0),
+                enabled: true,
+            });
+
+            return;
+        }
+    }
+
+    /// Splits a random connection in two, with a new node in between;
+    /// the old connection gets disabled.
+    fn mutate_add_node(&mut self, rng: &mut dyn RngCore, innovations: &mut Innovations) {
+        let enabled: Vec<_> = (0..self.connections.len())
+            .filter(|&idx| self.connections[idx].enabled)
+            .collect();
+
+        let idx = match enabled.choose(rng) {
+            Some(&idx) => idx,
+            None => return,
+        };
+
+        self.connections[idx].enabled = false;
+
+        let split = self.connections[idx].clone();
+        let mut node = innovations.split(split.innovation);
+
+        // This very genome has already split this connection before (and
+        // then got it re-enabled through crossover)
+        if self.node(node).is_some() {
+            node = innovations.node();
+        }
+
+        self.push_node(NodeGene {
+            id: node,
+            kind: NodeKind::Hidden,
+            bias: 0.0,
+        });
+
+        // Weights are chosen so that - at first - the new node changes
+        // the network's behavior as little as possible
+        self.push_connection(ConnectionGene {
+            innovation: innovations.connection(split.from, node),
+            from: split.from,
+            to: node,
+            weight: 1.0,
+            enabled: true,
+        });
+
+        self.push_connection(ConnectionGene {
+            innovation: innovations.connection(node, split.to),
+            from: node,
+            to: split.to,
+            weight: split.weight,
+            enabled: true,
+        });
+    }
+
+    fn connects(&self, from: usize, to: usize) -> bool {
+        self.connections
+            .iter()
+            .any(|connection| connection.from == from && connection.to == to)
+    }
+
+    /// Whether there's a path (made of any connections, including the
+    /// disabled ones - since they can get re-enabled) from `from` to `to`.
+    fn leads_to(&self, from: usize, to: usize) -> bool {
+        let mut pending = vec![from];
+        let mut visited = vec![from];
+
+        while let Some(node) = pending.pop() {
+            if node == to {
+                return true;
+            }
+
+            for connection in self.connections.iter().filter(|c| c.from == node) {
+                if !visited.contains(&connection.to) {
+                    visited.push(connection.to);
+                    pending.push(connection.to);
+                }
+            }
+        }
+
+        false
+    }
+
+    fn push_node(&mut self, node: NodeGene) {
+        let idx = self.nodes.partition_point(|other| other.id < node.id);
+        self.nodes.insert(idx, node);
+    }
+
+    fn push_connection(&mut self, connection: ConnectionGene) {
+        let idx = self
+            .connections
+            .partition_point(|other| other.innovation < connection.innovation);
+
+        self.connections.insert(idx, connection);
+    }
+}
+
+impl Innovations {
+    /// Creates a history that already knows about the nodes and
+    /// connections of `Genome::minimal()`.
+    pub fn new(inputs: usize, outputs: usize) -> Self {
+        let mut connections: BTreeMap<usize, BTreeMap<usize, usize>> = BTreeMap::new();
+
+        for from in 0..inputs {
+            for to in 0..outputs {
+                connections
+                    .entry(from)
+                    .or_default()
+                    .insert(inputs + to, from * outputs + to);
+            }
+        }
+
+        Self {
+            next_node: inputs + outputs,
+            next_innovation: inputs * outputs,
+            connections,
+            splits: BTreeMap::new(),
+        }
+    }
+
+    fn node(&mut self) -> usize {
+        self.next_node += 1;
+        self.next_node - 1
+    }
+
+    fn connection(&mut self, from: usize, to: usize) -> usize {
+        let next_innovation = &mut self.next_innovation;
+
+        *self
+            .connections
+            .entry(from)
+            .or_default()
+            .entry(to)
+            .or_insert_with(|| {
+                *next_innovation += 1;
+                *next_innovation - 1
+            })
+    }
+
+    fn split(&mut self, innovation: usize) -> usize {
+        if let Some(&node) = self.splits.get(&innovation) {
+            return node;
+        }
+
+        let node = self.node();
+        self.splits.insert(innovation, node);
+        node
+    }
+}
+
+impl Default for Compatibility {
+    fn default() -> Self {
+        Self {
+            excess_coeff: 1.0,
+            disjoint_coeff: 1.0,
+            weight_coeff: 0.4,
+            threshold: 3.0,
+        }
+    }
+}
+
+impl Neat {
+    /// Prepares evolution of genomes created with
+    /// `Genome::minimal(rng, inputs, outputs)`.
+    pub fn new(inputs: usize, outputs: usize) -> Self {
+        Self {
+            innovations: Innovations::new(inputs, outputs),
+            compatibility: Compatibility::default(),
+            weight_mut_chance: 0.8,
+            weight_mut_coeff: 0.5,
+            add_node_chance: 0.03,
+            add_connection_chance: 0.05,
+            survival_fraction: 0.5,
+        }
+    }
+
+    pub fn with_compatibility(mut self, compatibility: Compatibility) -> Self {
+        self.compatibility = compatibility;
+        self
+    }
+
+    pub fn with_weight_mutation(self, chance: f32, coeff: f32) -> Self {
+        self.try_with_weight_mutation(chance, coeff)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_with_weight_mutation(mut self, chance: f32, coeff: f32) -> Result<Self, Error> {
+        check_chance(chance)?;
+
+        self.weight_mut_chance = chance;
+        self.weight_mut_coeff = coeff;
+        Ok(self)
+    }
+
+    pub fn with_structural_mutation(self, add_node_chance: f32, add_connection_chance: f32) -> Self {
+        self.try_with_structural_mutation(add_node_chance, add_connection_chance)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_with_structural_mutation(
+        mut self,
+        add_node_chance: f32,
+        add_connection_chance: f32,
+    ) -> Result<Self, Error> {
+        check_chance(add_node_chance)?;
+        check_chance(add_connection_chance)?;
+
+        self.add_node_chance = add_node_chance;
+        self.add_connection_chance = add_connection_chance;
+        Ok(self)
+    }
+
+    /// Groups the population into species of mutually compatible genomes;
+    /// returns indices of each species' members.
+    ///
+    /// Each species is represented by its first member, so - unlike in
+    /// the original paper - species don't carry over between
+    /// generations.
+    pub fn speciate<I>(&self, population: &[I]) -> Vec<Vec<usize>>
+    where
+        I: NeatIndividual,
+    {
+        let mut species: Vec<Vec<usize>> = Vec::new();
+
+        for (idx, individual) in population.iter().enumerate() {
+            let genome = individual.genome();
+
+            let compatible = species.iter_mut().find(|members| {
+                let representative = population[members[0]].genome();
+
+                genome.compatibility(representative, &self.compatibility)
+                    < self.compatibility.threshold
+            });
+
+            match compatible {
+                Some(members) => members.push(idx),
+                None => species.push(vec![idx]),
+            }
+        }
+
+        species
+    }
+
+    pub fn evolve<I>(&mut self, rng: &mut dyn RngCore, population: &[I]) -> (Vec<I>, Statistics)
+    where
+        I: NeatIndividual,
+    {
+        assert!(!population.is_empty());
+
+        let species = self.speciate(population);
+        let allotments = Self::allot(population, &species);
+
+        let mut new_population = Vec::with_capacity(population.len());
+
+        for (members, allotment) in species.into_iter().zip(allotments) {
+            if allotment == 0 {
+                continue;
+            }
+
+            let mut members: Vec<_> = members.into_iter().map(|idx| &population[idx]).collect();
+
+            members.sort_by(|a, b| {
+                b.fitness()
+                    .partial_cmp(&a.fitness())
+                    .expect("got a NaN fitness")
+            });
+
+            // Champions of the larger species survive unchanged
+            let elites = if members.len() >= 5 { 1 } else { 0 };
+
+            for _ in 0..elites {
+                new_population.push(I::create(members[0].genome().clone()));
+            }
+
+            let parents = ((members.len() as f32 * self.survival_fraction).ceil() as usize).max(1);
+            let parents = &members[..parents];
+
+            for _ in elites..allotment {
+                let a = parents.choose(rng).unwrap();
+                let b = parents.choose(rng).unwrap();
+
+                let (fitter, other) = if a.fitness() >= b.fitness() { (a, b) } else { (b, a) };
+
+                new_population.push(I::create(self.offspring(rng, fitter.genome(), other.genome())));
+            }
+        }
+
+        let stats = Statistics::from_fitnesses(
+            population
+                .iter()
+                .map(|individual| individual.fitness())
+                .collect(),
+        );
+
+        (new_population, stats)
+    }
+
+    /// Creates a single child by crossing given parents over and then
+    /// mutating the result - for populations that reproduce
+    /// continuously instead of generation by generation.
+    pub fn offspring(&mut self, rng: &mut dyn RngCore, fitter: &Genome, other: &Genome) -> Genome {
+        let mut child = Genome::crossover(rng, fitter, other);
+
+        child.mutate_weights(rng, self.weight_mut_chance, self.weight_mut_coeff);
+
+        if rng.gen_bool(self.add_connection_chance as _) {
+            child.mutate_add_connection(rng, &mut self.innovations);
+        }
+
+        if rng.gen_bool(self.add_node_chance as _) {
+            child.mutate_add_node(rng, &mut self.innovations);
+        }
+
+        child
+    }
+
+    /// Renumbers a genome that's evolved elsewhere (e.g. in another run,
+    /// with a history of its own) into this history, so that it can be
+    /// crossed over with the local genomes.
+    ///
+    /// Hidden nodes are matched through the connections they've split and
+    /// connections through the nodes they connect; whatever this history
+    /// hasn't seen yet gets a fresh id or innovation number.
+    ///
+    /// Genome has to have as many inputs & outputs as the genomes this
+    /// history's been created for (see `new()`); panics when any of its
+    /// connections leads to a node it doesn't have.
+    pub fn adopt(&mut self, genome: &Genome) -> Genome {
+        let mut ids = BTreeMap::new();
+        let mut nodes = Vec::with_capacity(genome.nodes.len());
+
+        // Nodes are sorted by id and ids are handed out in order, so each
+        // split connection's ends get renumbered before the node that's
+        // split it
+        for (idx, node) in genome.nodes.iter().enumerate() {
+            let id = match node.kind {
+                NodeKind::Input | NodeKind::Output => idx,
+
+                NodeKind::Hidden => {
+                    let split = genome.connections.iter().find(|split| {
+                        ids.contains_key(&split.from)
+                            && ids.contains_key(&split.to)
+                            && genome.connects(split.from, node.id)
+                            && genome.connects(node.id, split.to)
+                    });
+
+                    let id = match split {
+                        Some(split) => {
+                            let innovation =
+                                self.innovations.connection(ids[&split.from], ids[&split.to]);
+
+                            self.innovations.split(innovation)
+                        }
+                        None => self.innovations.node(),
+                    };
+
+                    // Genome has split the same connection twice (see
+                    // `Genome::mutate_add_node()`)
+                    if ids.values().any(|&other| other == id) {
+                        self.innovations.node()
+                    } else {
+                        id
+                    }
+                }
+            };
+
+            ids.insert(node.id, id);
+            nodes.push(NodeGene { id, ..node.clone() });
+        }
+
+        let mut connections: Vec<_> = genome
+            .connections
+            .iter()
+            .map(|connection| {
+                let from = ids[&connection.from];
+                let to = ids[&connection.to];
+
+                ConnectionGene {
+                    innovation: self.innovations.connection(from, to),
+                    from,
+                    to,
+                    ..connection.clone()
+                }
+            })
+            .collect();
+
+        nodes.sort_by_key(|node| node.id);
+        connections.sort_by_key(|connection| connection.innovation);
+
+        Genome { nodes, connections }
+    }
+
+    /// Decides how many children each species gets, proportionally to its
+    /// shared fitness (i.e. its members' mean fitness) - so that a
+    /// single large species can't take over the entire population.
+    fn allot<I>(population: &[I], species: &[Vec<usize>]) -> Vec<usize>
+    where
+        I: NeatIndividual,
+    {
+        let scores: Vec<f32> = species
+            .iter()
+            .map(|members| {
+                members
+                    .iter()
+                    .map(|&idx| population[idx].fitness())
+                    .sum::<f32>()
+                    / members.len() as f32
+            })
+            .collect();
+
+        // Shares can't be negative, so - when some species have scored
+        // below zero - all the scores get shifted up, with the worst
+        // species ending up at zero
+        let lowest = scores.iter().copied().fold(0.0, f32::min);
+        let scores: Vec<_> = scores.iter().map(|score| score - lowest).collect();
+
+        let total: f32 = scores.iter().sum();
+
+        // When nobody's scored anything, each species just keeps its size
+        if total <= 0.0 {
+            return species.iter().map(|members| members.len()).collect();
+        }
+
+        let shares: Vec<_> = scores
+            .iter()
+            .map(|score| score / total * population.len() as f32)
+            .collect();
+
+        let mut allotments: Vec<_> = shares.iter().map(|share| share.floor() as usize).collect();
+
+        // Rounding down leaves a few places free - they go to the species
+        // that have been rounded down the most
+        let mut by_remainder: Vec<_> = (0..shares.len()).collect();
+
+        by_remainder.sort_by(|&a, &b| {
+            let a = shares[a] - shares[a].floor();
+            let b = shares[b] - shares[b].floor();
+            b.partial_cmp(&a).expect("got a NaN fitness")
+        });
+
+        let missing = population.len().saturating_sub(allotments.iter().sum());
+
+        for idx in by_remainder.into_iter().take(missing) {
+            allotments[idx] += 1;
+        }
+
+        // Rounding errors can make the shares add up to a bit more than
+        // the population - those extra places get taken from the largest
+        // species
+        while allotments.iter().sum::<usize>() > population.len() {
+            let largest = (0..allotments.len()).max_by_key(|&idx| allotments[idx]).unwrap();
+            allotments[largest] -= 1;
+        }
+
+        allotments
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[derive(Clone, Debug)]
+    struct TestIndividual {
+        genome: Genome,
+        fitness: f32,
+    }
+
+    impl NeatIndividual for TestIndividual {
+        fn create(genome: Genome) -> Self {
+            Self {
+                genome,
+                fitness: 0.0,
+            }
+        }
+
+        fn genome(&self) -> &Genome {
+            &self.genome
+        }
+
+        fn fitness(&self) -> f32 {
+            self.fitness
+        }
+    }
+
+    fn connection(innovation: usize, from: usize, to: usize, weight: f32) -> ConnectionGene {
+        ConnectionGene {
+            innovation,
+            from,
+            to,
+            weight,
+            enabled: true,
+        }
+    }
+
+    mod minimal {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let genome = Genome::minimal(&mut rng, 3, 2);
+
+            let kinds: Vec<_> = genome.nodes().iter().map(|node| node.kind).collect();
+
+            assert_eq!(
+                kinds,
+                vec![
+                    NodeKind::Input,
+                    NodeKind::Input,
+                    NodeKind::Input,
+                    NodeKind::Output,
+                    NodeKind::Output,
+                ]
+            );
+
+            let links: Vec<_> = genome
+                .connections()
+                .iter()
+                .map(|c| (c.innovation, c.from, c.to))
+                .collect();
+
+            assert_eq!(
+                links,
+                vec![(0, 0, 3), (1, 0, 4), (2, 1, 3), (3, 1, 4), (4, 2, 3), (5, 2, 4)]
+            );
+        }
+    }
+
+    mod compatibility {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let genome = |connections| Genome {
+                nodes: Vec::new(),
+                connections,
+            };
+
+            let a = genome(vec![
+                connection(0, 0, 2, 1.0),
+                connection(1, 1, 2, 1.0),
+                connection(3, 0, 3, 0.5),
+            ]);
+
+            let b = genome(vec![
+                connection(0, 0, 2, 0.0),
+                connection(2, 1, 3, 1.0),
+                connection(3, 0, 3, 0.5),
+                connection(4, 3, 2, 1.0),
+                connection(5, 0, 1, 1.0),
+            ]);
+
+            let coeffs = Compatibility {
+                excess_coeff: 1.0,
+                disjoint_coeff: 2.0,
+                weight_coeff: 4.0,
+                threshold: 0.0,
+            };
+
+            // 2 excess, 2 disjoint (innovations 1 & 2), mean weight
+            // difference of (1.0 + 0.0) / 2
+            approx::assert_relative_eq!(a.compatibility(&b, &coeffs), 2.0 + 4.0 + 2.0);
+            approx::assert_relative_eq!(b.compatibility(&a, &coeffs), 2.0 + 4.0 + 2.0);
+            approx::assert_relative_eq!(a.compatibility(&a, &coeffs), 0.0);
+        }
+    }
+
+    mod crossover {
+        use super::*;
+
+        #[test]
+        fn takes_structure_from_the_fitter_parent() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut innovations = Innovations::new(2, 1);
+
+            let fitter = Genome::minimal(&mut rng, 2, 1);
+            let mut other = fitter.clone();
+            other.mutate_add_node(&mut rng, &mut innovations);
+
+            let child = Genome::crossover(&mut rng, &fitter, &other);
+
+            assert_eq!(child.nodes().len(), fitter.nodes().len());
+            assert_eq!(child.connections().len(), fitter.connections().len());
+        }
+    }
+
+    mod mutate_add_node {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut innovations = Innovations::new(1, 1);
+            let mut genome = Genome::minimal(&mut rng, 1, 1);
+            let weight = genome.connections()[0].weight;
+
+            genome.mutate_add_node(&mut rng, &mut innovations);
+
+            assert_eq!(genome.nodes().len(), 3);
+            assert_eq!(genome.nodes()[2].kind, NodeKind::Hidden);
+
+            assert_eq!(
+                genome.connections(),
+                &[
+                    ConnectionGene {
+                        enabled: false,
+                        ..connection(0, 0, 1, weight)
+                    },
+                    connection(1, 0, 2, 1.0),
+                    connection(2, 2, 1, weight),
+                ]
+            );
+
+            // Same mutation in another genome yields the same numbers
+            let mut other = Genome::minimal(&mut rng, 1, 1);
+            other.mutate_add_node(&mut rng, &mut innovations);
+
+            let numbers = |genome: &Genome| -> Vec<_> {
+                genome.connections().iter().map(|c| c.innovation).collect()
+            };
+
+            assert_eq!(numbers(&genome), numbers(&other));
+        }
+    }
+
+    mod mutate_add_connection {
+        use super::*;
+
+        #[test]
+        fn never_creates_cycles() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut innovations = Innovations::new(2, 2);
+            let mut genome = Genome::minimal(&mut rng, 2, 2);
+
+            for _ in 0..50 {
+                genome.mutate_add_node(&mut rng, &mut innovations);
+                genome.mutate_add_connection(&mut rng, &mut innovations);
+            }
+
+            for connection in genome.connections() {
+                assert!(!genome.leads_to(connection.to, connection.from));
+            }
+        }
+    }
+
+    mod try_with_mutation {
+        use super::*;
+
+        #[test]
+        fn test() {
+            assert!(Neat::new(1, 1).try_with_weight_mutation(0.5, 2.0).is_ok());
+            assert!(Neat::new(1, 1).try_with_structural_mutation(0.0, 1.0).is_ok());
+
+            assert_eq!(
+                Neat::new(1, 1).try_with_weight_mutation(1.5, 2.0).err(),
+                Some(Error::InvalidChance(1.5)),
+            );
+
+            assert_eq!(
+                Neat::new(1, 1).try_with_structural_mutation(0.5, -0.5).err(),
+                Some(Error::InvalidChance(-0.5)),
+            );
+        }
+    }
+
+    mod allot {
+        use super::*;
+
+        fn allotments(fitnesses: &[&[f32]]) -> Vec<usize> {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut population = Vec::new();
+            let mut species = Vec::new();
+
+            for fitnesses in fitnesses {
+                let members = (population.len()..population.len() + fitnesses.len()).collect();
+                species.push(members);
+
+                for &fitness in fitnesses.iter() {
+                    population.push(TestIndividual {
+                        genome: Genome::minimal(&mut rng, 1, 1),
+                        fitness,
+                    });
+                }
+            }
+
+            Neat::allot(&population, &species)
+        }
+
+        #[test]
+        fn test() {
+            assert_eq!(allotments(&[&[3.0, 3.0], &[1.0, 1.0]]), vec![3, 1]);
+            assert_eq!(allotments(&[&[0.0, 0.0, 0.0], &[0.0]]), vec![3, 1]);
+        }
+
+        #[test]
+        fn given_negative_fitnesses() {
+            assert_eq!(allotments(&[&[3.0, 3.0], &[-1.0, -1.0]]), vec![4, 0]);
+            assert_eq!(allotments(&[&[3.0], &[1.0], &[-1.0]]), vec![2, 1, 0]);
+            assert_eq!(allotments(&[&[-1.0, -1.0], &[-1.0]]), vec![2, 1]);
+        }
+    }
+
+    mod adopt {
+        use super::*;
+
+        #[test]
+        fn renumbers_into_local_history() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            // Both runs split the same connection, but - since the foreign
+            // one has done something else first - they've numbered it
+            // differently
+            let mut foreign = Neat::new(2, 1);
+            let mut genome = Genome::minimal(&mut rng, 2, 1);
+            foreign.innovations.node();
+            foreign.innovations.connection(0, 1);
+            genome.connections[1].enabled = false;
+            genome.mutate_add_node(&mut rng, &mut foreign.innovations);
+
+            let mut local = Neat::new(2, 1);
+            let mut native = Genome::minimal(&mut rng, 2, 1);
+            native.connections[1].enabled = false;
+            native.mutate_add_node(&mut rng, &mut local.innovations);
+
+            assert_ne!(genome.nodes()[3].id, native.nodes()[3].id);
+
+            let adopted = local.adopt(&genome);
+
+            let links = |genome: &Genome| -> Vec<_> {
+                genome
+                    .connections()
+                    .iter()
+                    .map(|c| (c.innovation, c.from, c.to, c.enabled))
+                    .collect()
+            };
+
+            assert_eq!(adopted.nodes()[3].id, native.nodes()[3].id);
+            assert_eq!(links(&adopted), links(&native));
+
+            let weights = |genome: &Genome| -> Vec<_> { genome.weights().collect() };
+            assert_eq!(weights(&adopted), weights(&genome));
+        }
+    }
+
+    mod evolve {
+        use super::*;
+
+        #[test]
+        fn keeps_population_size() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut neat = Neat::new(3, 2).with_structural_mutation(0.5, 0.5);
+
+            let mut population: Vec<_> = (0..20)
+                .map(|_| TestIndividual::create(Genome::minimal(&mut rng, 3, 2)))
+                .collect();
+
+            for _ in 0..10 {
+                for individual in &mut population {
+                    individual.fitness = individual.genome.connections().len() as f32;
+                }
+
+                population = neat.evolve(&mut rng, &population).0;
+                assert_eq!(population.len(), 20);
+            }
+
+            // Structural mutations are rewarded, so genomes have grown
+            assert!(population
+                .iter()
+                .any(|individual| individual.genome.connections().len() > 6));
+        }
+
+        #[test]
+        fn separates_incompatible_genomes() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let neat = Neat::new(2, 1);
+
+            let genome = Genome::minimal(&mut rng, 2, 1);
+            let mut distant = genome.clone();
+
+            for connection in &mut distant.connections {
+                connection.weight += 10.0;
+            }
+
+            let population: Vec<_> = vec![genome.clone(), distant, genome]
+                .into_iter()
+                .map(TestIndividual::create)
+                .collect();
+
+            assert_eq!(neat.speciate(&population), vec![vec![0, 2], vec![1]]);
+        }
+    }
+}
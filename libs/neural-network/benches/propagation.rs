@@ -0,0 +1,72 @@
+//! Compares propagating inputs neuron by neuron (through the `Neuron`
+//! views) and one by one against propagating them all at once, as a
+//! batch:
+//!
+//! ```shell
+//! $ cargo bench -p lib-neural-network
+//! ```
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use lib_neural_network::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// Number of inputs propagated at once - roughly, the size of a
+/// population
+const BATCHES: [usize; 3] = [10, 100, 1000];
+
+fn propagation(c: &mut Criterion) {
+    let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+    // Same shape as the simulation's brains
+    let network = Network::random(
+        &mut rng,
+        &[
+            LayerTopology { neurons: 9, activation: Activation::Identity, recurrent: false },
+            LayerTopology { neurons: 18, activation: Activation::Relu, recurrent: false },
+            LayerTopology { neurons: 2, activation: Activation::Relu, recurrent: false },
+        ],
+    );
+
+    let mut group = c.benchmark_group("propagation");
+
+    for &batch in &BATCHES {
+        let inputs: Vec<Vec<f32>> = (0..batch)
+            .map(|_| (0..9).map(|_| rng.gen()).collect())
+            .collect();
+
+        group.bench_with_input(BenchmarkId::new("neurons", batch), &inputs, |b, inputs| {
+            b.iter(|| {
+                inputs
+                    .iter()
+                    .map(|inputs| {
+                        network.layers().iter().fold(inputs.clone(), |inputs, layer| {
+                            layer
+                                .neurons()
+                                .map(|neuron| neuron.propagate(&inputs, layer.activation()))
+                                .collect()
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("one_by_one", batch), &inputs, |b, inputs| {
+            b.iter(|| {
+                inputs
+                    .iter()
+                    .map(|inputs| network.propagate(inputs.clone()))
+                    .collect::<Vec<_>>()
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("batch", batch), &inputs, |b, inputs| {
+            b.iter(|| network.propagate_batch(inputs))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, propagation);
+criterion_main!(benches);
@@ -0,0 +1,121 @@
+use crate::*;
+
+/// Slope of `Activation::LeakyRelu` for negative inputs
+const LEAKY_RELU_SLOPE: f32 = 0.01;
+
+/// Function applied to neuron's weighted sum of inputs
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Activation {
+    /// `max(0, x)`
+    Relu,
+
+    /// `x` for positive inputs, `0.01 * x` for negative ones
+    LeakyRelu,
+
+    /// `1 / (1 + e^-x)`, squashes into `(0, 1)`
+    Sigmoid,
+
+    /// Hyperbolic tangent, squashes into `(-1, 1)`
+    Tanh,
+
+    /// `x`, i.e. no activation at all
+    Identity,
+
+    /// `1` for positive inputs, `0` otherwise
+    Step,
+}
+
+impl Activation {
+    pub fn apply(self, x: f32) -> f32 {
+        match self {
+            Self::Relu => x.max(0.0),
+            Self::LeakyRelu => {
+                if x > 0.0 {
+                    x
+                } else {
+                    LEAKY_RELU_SLOPE * x
+                }
+            }
+            Self::Sigmoid => 1.0 / (1.0 + (-x).exp()),
+            Self::Tanh => x.tanh(),
+            Self::Identity => x,
+            Self::Step => {
+                if x > 0.0 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+impl Default for Activation {
+    fn default() -> Self {
+        Self::Relu
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod apply {
+        use super::*;
+
+        fn actual(activation: Activation) -> Vec<f32> {
+            [-2.0, -0.5, 0.0, 0.5, 2.0]
+                .iter()
+                .map(|&x| activation.apply(x))
+                .collect()
+        }
+
+        #[test]
+        fn relu() {
+            let actual = actual(Activation::Relu);
+            let expected = [0.0, 0.0, 0.0, 0.5, 2.0];
+
+            approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+        }
+
+        #[test]
+        fn leaky_relu() {
+            let actual = actual(Activation::LeakyRelu);
+            let expected = [-0.02, -0.005, 0.0, 0.5, 2.0];
+
+            approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+        }
+
+        #[test]
+        fn sigmoid() {
+            let actual = actual(Activation::Sigmoid);
+            let expected = [0.11920292, 0.37754068, 0.5, 0.62245935, 0.8807971];
+
+            approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+        }
+
+        #[test]
+        fn tanh() {
+            let actual = actual(Activation::Tanh);
+            let expected = [-0.9640276, -0.46211717, 0.0, 0.46211717, 0.9640276];
+
+            approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+        }
+
+        #[test]
+        fn identity() {
+            let actual = actual(Activation::Identity);
+            let expected = [-2.0, -0.5, 0.0, 0.5, 2.0];
+
+            approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+        }
+
+        #[test]
+        fn step() {
+            let actual = actual(Activation::Step);
+            let expected = [0.0, 0.0, 0.0, 1.0, 1.0];
+
+            approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    /// Network needs at least an input and an output layer
+    NotEnoughLayers,
+
+    EmptyLayer,
+
+    /// Neuron needs at least one weight (not counting its bias)
+    NoWeights,
+
+    /// Layer's neurons have to have the same number of weights each
+    MismatchedWeights,
+
+    NotEnoughWeights,
+    TooManyWeights,
+
+    /// Graph network has fewer (non-input) nodes than outputs
+    NotEnoughNodes,
+
+    /// Graph network's connection leads from or to a node that doesn't
+    /// exist
+    UnknownNode(usize),
+
+    /// Graph network's connection leads into one of its inputs
+    ConnectionIntoInput(usize),
+
+    /// Graph network isn't feed-forward
+    Cycle,
+
+    /// Recurrent layer needs more inputs than outputs, since the last
+    /// `outputs` of them are its own previous outputs
+    InvalidRecurrence,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotEnoughLayers => write!(f, "got less than two layers"),
+            Self::EmptyLayer => write!(f, "got a layer without neurons"),
+            Self::NoWeights => write!(f, "got a neuron without weights"),
+            Self::MismatchedWeights => {
+                write!(f, "got neurons with different numbers of weights")
+            }
+            Self::NotEnoughWeights => write!(f, "got not enough weights"),
+            Self::TooManyWeights => write!(f, "got too many weights"),
+            Self::NotEnoughNodes => write!(f, "got fewer nodes than outputs"),
+            Self::UnknownNode(node) => write!(f, "got connection to an unknown node: {}", node),
+            Self::ConnectionIntoInput(node) => write!(f, "got connection into an input: {}", node),
+            Self::Cycle => write!(f, "got a cycle"),
+            Self::InvalidRecurrence => {
+                write!(f, "recurrent layer must have more inputs than outputs")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,16 @@
+use crate::*;
+
+/// Self-describing form of a `Network` - its topology (activations
+/// included) along with its weights - that can be stored and loaded
+/// independently of whatever program has created the network.
+///
+/// See `Network::export()` and `Network::try_import()`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExportedNetwork {
+    /// Network's layers, starting with the input one
+    pub topology: Vec<LayerTopology>,
+
+    /// Network's weights, in the order `Network::from_weights()` expects
+    /// them
+    pub weights: Vec<f32>,
+}
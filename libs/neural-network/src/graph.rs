@@ -0,0 +1,282 @@
+use crate::*;
+
+/// Neuron of a `GraphNetwork` (inputs don't have any)
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GraphNode {
+    pub bias: f32,
+    pub activation: Activation,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GraphConnection {
+    pub from: usize,
+    pub to: usize,
+    pub weight: f32,
+}
+
+/// Feed-forward network of an arbitrary (acyclic) shape - as opposed to
+/// `Network`, which is made of fully-connected layers.
+///
+/// Nodes are addressed by indices: the first `inputs` ones are the
+/// network's inputs, then go its outputs, then the hidden nodes.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GraphNetwork {
+    inputs: usize,
+    outputs: usize,
+
+    /// Non-input nodes, i.e. `nodes[0]` is node number `inputs`
+    nodes: Vec<GraphNode>,
+
+    /// (from, weight) of connections coming into each non-input node
+    incoming: Vec<Vec<(usize, f32)>>,
+
+    /// Non-input nodes, sorted topologically
+    order: Vec<usize>,
+}
+
+impl GraphNetwork {
+    pub fn new(
+        inputs: usize,
+        outputs: usize,
+        nodes: Vec<GraphNode>,
+        connections: &[GraphConnection],
+    ) -> Self {
+        Self::try_new(inputs, outputs, nodes, connections).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(
+        inputs: usize,
+        outputs: usize,
+        nodes: Vec<GraphNode>,
+        connections: &[GraphConnection],
+    ) -> Result<Self, Error> {
+        if inputs == 0 || outputs == 0 {
+            return Err(Error::EmptyLayer);
+        }
+
+        if nodes.len() < outputs {
+            return Err(Error::NotEnoughNodes);
+        }
+
+        let len = inputs + nodes.len();
+        let mut incoming = vec![Vec::new(); nodes.len()];
+
+        for connection in connections {
+            if connection.from >= len {
+                return Err(Error::UnknownNode(connection.from));
+            }
+
+            if connection.to >= len {
+                return Err(Error::UnknownNode(connection.to));
+            }
+
+            if connection.to < inputs {
+                return Err(Error::ConnectionIntoInput(connection.to));
+            }
+
+            incoming[connection.to - inputs].push((connection.from, connection.weight));
+        }
+
+        let order = Self::sort(inputs, &incoming).ok_or(Error::Cycle)?;
+
+        Ok(Self {
+            inputs,
+            outputs,
+            nodes,
+            incoming,
+            order,
+        })
+    }
+
+    pub fn propagate(&self, inputs: Vec<f32>) -> Vec<f32> {
+        let mut values = self.trace(inputs);
+        values.truncate(self.outputs);
+        values
+    }
+
+    /// Same as `propagate()`, but returns values of all the non-input
+    /// nodes: outputs first, then the hidden ones.
+    pub fn trace(&self, inputs: Vec<f32>) -> Vec<f32> {
+        assert_eq!(inputs.len(), self.inputs);
+
+        let mut values = inputs;
+        values.resize(self.inputs + self.nodes.len(), 0.0);
+
+        for &node in &self.order {
+            let sum = self.incoming[node]
+                .iter()
+                .map(|&(from, weight)| values[from] * weight)
+                .sum::<f32>();
+
+            let GraphNode { bias, activation } = self.nodes[node];
+
+            values[self.inputs + node] = activation.apply(sum + bias);
+        }
+
+        values.drain(..self.inputs);
+        values
+    }
+
+    pub fn outputs(&self) -> usize {
+        self.outputs
+    }
+
+    /// Orders non-input nodes so that each one goes after all of the
+    /// nodes it depends on (Kahn's algorithm); returns `None` when
+    /// there's a cycle.
+    fn sort(inputs: usize, incoming: &[Vec<(usize, f32)>]) -> Option<Vec<usize>> {
+        let mut pending: Vec<_> = incoming
+            .iter()
+            .map(|from| from.iter().filter(|&&(from, _)| from >= inputs).count())
+            .collect();
+
+        let mut outgoing = vec![Vec::new(); incoming.len()];
+
+        for (to, from) in incoming.iter().enumerate() {
+            for &(from, _) in from {
+                if from >= inputs {
+                    outgoing[from - inputs].push(to);
+                }
+            }
+        }
+
+        let mut ready: Vec<_> = (0..incoming.len()).filter(|&node| pending[node] == 0).collect();
+        let mut order = Vec::with_capacity(incoming.len());
+
+        while let Some(node) = ready.pop() {
+            order.push(node);
+
+            for &to in &outgoing[node] {
+                pending[to] -= 1;
+
+                if pending[to] == 0 {
+                    ready.push(to);
+                }
+            }
+        }
+
+        if order.len() == incoming.len() {
+            Some(order)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(bias: f32) -> GraphNode {
+        GraphNode {
+            bias,
+            activation: Activation::Identity,
+        }
+    }
+
+    fn connection(from: usize, to: usize, weight: f32) -> GraphConnection {
+        GraphConnection { from, to, weight }
+    }
+
+    mod propagate {
+        use super::*;
+
+        #[test]
+        fn test() {
+            // 0 ---> 2 (output)
+            //  \     ^
+            //   `-> 3 (hidden) <- 1
+            let network = GraphNetwork::new(
+                2,
+                1,
+                vec![node(0.5), node(-1.0)],
+                &[
+                    connection(3, 2, 2.0),
+                    connection(0, 2, 1.0),
+                    connection(0, 3, 0.5),
+                    connection(1, 3, 1.0),
+                ],
+            );
+
+            // hidden = 0.5 * 2.0 + 1.0 * 3.0 - 1.0 = 3.0
+            // output = 2.0 * 3.0 + 1.0 * 2.0 + 0.5 = 8.5
+            let actual = network.propagate(vec![2.0, 3.0]);
+            approx::assert_relative_eq!(actual.as_slice(), [8.5].as_slice());
+
+            let actual = network.trace(vec![2.0, 3.0]);
+            approx::assert_relative_eq!(actual.as_slice(), [8.5, 3.0].as_slice());
+        }
+
+        #[test]
+        fn applies_activations() {
+            let network = GraphNetwork::new(
+                1,
+                2,
+                vec![
+                    GraphNode {
+                        bias: 0.0,
+                        activation: Activation::Relu,
+                    },
+                    GraphNode {
+                        bias: 0.0,
+                        activation: Activation::Step,
+                    },
+                ],
+                &[connection(0, 1, -1.0), connection(0, 2, 1.0)],
+            );
+
+            let actual = network.propagate(vec![0.5]);
+            approx::assert_relative_eq!(actual.as_slice(), [0.0, 1.0].as_slice());
+        }
+
+        #[test]
+        fn disconnected_outputs_yield_their_bias() {
+            let network = GraphNetwork::new(1, 1, vec![node(0.25)], &[]);
+
+            let actual = network.propagate(vec![1.0]);
+            approx::assert_relative_eq!(actual.as_slice(), [0.25].as_slice());
+        }
+    }
+
+    mod try_new {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let network = |connections: &[GraphConnection]| {
+                GraphNetwork::try_new(1, 1, vec![node(0.0), node(0.0)], connections).err()
+            };
+
+            assert_eq!(network(&[connection(0, 3, 1.0)]), Some(Error::UnknownNode(3)));
+            assert_eq!(network(&[connection(3, 1, 1.0)]), Some(Error::UnknownNode(3)));
+            assert_eq!(network(&[connection(1, 0, 1.0)]), Some(Error::ConnectionIntoInput(0)));
+
+            assert_eq!(
+                network(&[connection(2, 1, 1.0), connection(1, 2, 1.0)]),
+                Some(Error::Cycle),
+            );
+
+            assert_eq!(
+                GraphNetwork::try_new(1, 2, vec![node(0.0)], &[]).err(),
+                Some(Error::NotEnoughNodes),
+            );
+
+            assert_eq!(network(&[connection(0, 2, 1.0), connection(2, 1, 1.0)]), None);
+        }
+    }
+
+    mod new {
+        use super::*;
+
+        #[test]
+        #[should_panic(expected = "got a cycle")]
+        fn rejects_cycles() {
+            GraphNetwork::new(
+                1,
+                1,
+                vec![node(0.0), node(0.0)],
+                &[connection(0, 2, 1.0), connection(2, 1, 1.0), connection(1, 2, 1.0)],
+            );
+        }
+    }
+}
@@ -1,46 +1,315 @@
 use crate::*;
+use serde::{de, Deserializer, Serializer};
 
+/// Fully-connected layer, stored as a weight matrix - so that propagating
+/// through it walks a single contiguous buffer instead of hopping from
+/// one neuron's `Vec` to another.
+///
+/// Neurons are still available as views into that matrix - see
+/// `neurons()`.
 #[derive(Clone, Debug)]
 pub struct Layer {
-    crate neurons: Vec<Neuron>,
+    crate biases: Vec<f32>,
+
+    /// Row-major, one row (of `input_size()` weights) per neuron
+    crate weights: Vec<f32>,
+
+    crate activation: Activation,
+    crate recurrent: bool,
 }
 
 impl Layer {
-    pub fn new(neurons: Vec<Neuron>) -> Self {
-        assert!(!neurons.is_empty());
+    pub fn new(neurons: Vec<Neuron>, activation: Activation) -> Self {
+        Self::try_new(neurons, activation).unwrap_or_else(|err| panic!("{}", err))
+    }
 
-        assert!(neurons
-            .iter()
-            .all(|neuron| neuron.weights.len() == neurons[0].weights.len()));
+    pub fn try_new(neurons: Vec<Neuron>, activation: Activation) -> Result<Self, Error> {
+        let input_size = match neurons.first() {
+            Some(neuron) => neuron.weights.len(),
+            None => return Err(Error::EmptyLayer),
+        };
+
+        if input_size == 0 {
+            return Err(Error::NoWeights);
+        }
+
+        if neurons.iter().any(|neuron| neuron.weights.len() != input_size) {
+            return Err(Error::MismatchedWeights);
+        }
+
+        let biases = neurons.iter().map(|neuron| neuron.bias).collect();
+
+        let weights = neurons
+            .into_iter()
+            .flat_map(|neuron| neuron.weights)
+            .collect();
 
-        Self { neurons }
+        Ok(Self {
+            biases,
+            weights,
+            activation,
+            recurrent: false,
+        })
+    }
+
+    /// Turns this layer into a recurrent one - the last `output_size()`
+    /// weights of each neuron then get applied to layer's own previous
+    /// outputs, instead of to the actual inputs.
+    pub fn with_recurrence(self) -> Self {
+        self.try_with_recurrence().unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_with_recurrence(mut self) -> Result<Self, Error> {
+        if self.input_size() <= self.output_size() {
+            return Err(Error::InvalidRecurrence);
+        }
+
+        self.recurrent = true;
+        Ok(self)
     }
 
     pub fn from_weights(
         input_size: usize,
         output_size: usize,
+        activation: Activation,
         weights: &mut dyn Iterator<Item = f32>,
     ) -> Self {
+        Self::try_from_weights(input_size, output_size, activation, weights)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_from_weights(
+        input_size: usize,
+        output_size: usize,
+        activation: Activation,
+        weights: &mut dyn Iterator<Item = f32>,
+    ) -> Result<Self, Error> {
         let neurons = (0..output_size)
-            .map(|_| Neuron::from_weights(input_size, weights))
-            .collect();
+            .map(|_| Neuron::try_from_weights(input_size, weights))
+            .collect::<Result<_, _>>()?;
 
-        Self::new(neurons)
+        Self::try_new(neurons, activation)
     }
 
-    pub fn random(rng: &mut dyn RngCore, input_neurons: usize, output_neurons: usize) -> Self {
+    pub fn random(
+        rng: &mut dyn RngCore,
+        input_neurons: usize,
+        output_neurons: usize,
+        activation: Activation,
+    ) -> Self {
         let neurons = (0..output_neurons)
             .map(|_| Neuron::random(rng, input_neurons))
             .collect();
 
-        Self::new(neurons)
+        Self::new(neurons, activation)
     }
 
-    pub fn propagate(&self, inputs: Vec<f32>) -> Vec<f32> {
-        self.neurons
+    pub fn neurons(&self) -> impl ExactSizeIterator<Item = NeuronRef<'_>> + '_ {
+        self.biases
             .iter()
-            .map(|neuron| neuron.propagate(&inputs))
-            .collect()
+            .zip(self.weights.chunks_exact(self.input_size()))
+            .map(|(&bias, weights)| NeuronRef { bias, weights })
+    }
+
+    pub fn neuron(&self, idx: usize) -> NeuronRef<'_> {
+        let input_size = self.input_size();
+
+        NeuronRef {
+            bias: self.biases[idx],
+            weights: &self.weights[idx * input_size..][..input_size],
+        }
+    }
+
+    pub fn activation(&self) -> Activation {
+        self.activation
+    }
+
+    pub fn is_recurrent(&self) -> bool {
+        self.recurrent
+    }
+
+    /// Number of inputs each neuron has (including the recurrent ones)
+    pub fn input_size(&self) -> usize {
+        self.weights.len() / self.biases.len()
+    }
+
+    pub fn output_size(&self) -> usize {
+        self.biases.len()
+    }
+
+    /// Number of inputs coming from outside of the layer (i.e. without
+    /// the recurrent ones)
+    pub fn outer_input_size(&self) -> usize {
+        if self.recurrent {
+            self.input_size() - self.output_size()
+        } else {
+            self.input_size()
+        }
+    }
+
+    /// For a recurrent layer, `inputs` have to be followed by layer's
+    /// previous outputs - see `propagate_with_state()`.
+    pub fn propagate(&self, inputs: Vec<f32>) -> Vec<f32> {
+        assert_eq!(inputs.len(), self.input_size());
+
+        let mut outputs = vec![0.0; self.output_size()];
+        self.propagate_into(&inputs, &mut outputs);
+        outputs
+    }
+
+    /// Propagates a batch of inputs at once: `inputs` contains
+    /// `outer_input_size()` values for each item of the batch, one item
+    /// after another; outputs are laid out the same way.
+    ///
+    /// Recurrent layers get propagated as if they had a blank memory.
+    pub fn propagate_batch(&self, inputs: &[f32]) -> Vec<f32> {
+        let input_size = self.outer_input_size();
+        let output_size = self.output_size();
+
+        assert_eq!(inputs.len() % input_size, 0);
+
+        let batch = inputs.len() / input_size;
+        let mut outputs = vec![0.0; batch * output_size];
+
+        // Items are taken `BATCH_LANES` at a time and transposed, so that
+        // each weight gets loaded once and multiplied by all the items of
+        // such block in one go (which the compiler can vectorize)
+        let mut block = vec![0.0; input_size * BATCH_LANES];
+
+        for (block_idx, items) in inputs.chunks(input_size * BATCH_LANES).enumerate() {
+            let len = items.len() / input_size;
+
+            for (item_idx, item) in items.chunks_exact(input_size).enumerate() {
+                for (input_idx, &input) in item.iter().enumerate() {
+                    block[input_idx * BATCH_LANES + item_idx] = input;
+                }
+            }
+
+            // (the last block might be partially filled - whatever's left
+            // in its unused lanes just doesn't get written anywhere)
+
+            // Blank memory counts as zeros, which (being multiplied by
+            // the recurrent weights) contribute nothing - so, instead of
+            // padding the inputs, it's enough to skip those weights
+            let rows = self.weights.chunks_exact(self.input_size());
+
+            for (neuron_idx, (row, bias)) in rows.zip(&self.biases).enumerate() {
+                let mut sums = [*bias; BATCH_LANES];
+
+                let columns = block.chunks_exact(BATCH_LANES);
+
+                for (weight, inputs) in row[..input_size].iter().zip(columns) {
+                    for (sum, input) in sums.iter_mut().zip(inputs) {
+                        *sum += weight * input;
+                    }
+                }
+
+                for (item_idx, sum) in sums[..len].iter().enumerate() {
+                    let output_idx = (block_idx * BATCH_LANES + item_idx) * output_size;
+
+                    outputs[output_idx + neuron_idx] = self.activation.apply(*sum);
+                }
+            }
+        }
+
+        outputs
+    }
+
+    /// Same as `propagate()`, but recurrent layers get propagated as if
+    /// they had a blank memory - i.e. `inputs` contains just the
+    /// `outer_input_size()` values.
+    crate fn propagate_blank(&self, inputs: &[f32]) -> Vec<f32> {
+        assert_eq!(inputs.len(), self.outer_input_size());
+
+        // (see `propagate_batch()`)
+        let mut outputs = vec![0.0; self.output_size()];
+        self.propagate_into(inputs, &mut outputs);
+        outputs
+    }
+
+    /// Same as `propagate()`, but - for a recurrent layer - reads layer's
+    /// previous outputs from `state` (empty state counts as zeros) and
+    /// then replaces them with the new ones.
+    crate fn propagate_with_state(&self, mut inputs: Vec<f32>, state: &mut Vec<f32>) -> Vec<f32> {
+        if !self.recurrent {
+            return self.propagate(inputs);
+        }
+
+        state.resize(self.output_size(), 0.0);
+        inputs.extend_from_slice(state);
+
+        let outputs = self.propagate(inputs);
+        state.clone_from(&outputs);
+
+        outputs
+    }
+
+    /// Multiplies the weight matrix by `inputs`; when there are fewer
+    /// inputs than columns, the remaining columns are skipped.
+    fn propagate_into(&self, inputs: &[f32], outputs: &mut [f32]) {
+        let rows = self.weights.chunks_exact(self.input_size());
+
+        for ((output, row), bias) in outputs.iter_mut().zip(rows).zip(&self.biases) {
+            let sum = row
+                .iter()
+                .zip(inputs)
+                .map(|(weight, input)| weight * input)
+                .sum::<f32>();
+
+            *output = self.activation.apply(bias + sum);
+        }
+    }
+}
+
+/// Number of items `Layer::propagate_batch()` works on at once
+const BATCH_LANES: usize = 8;
+
+/// Layers get serialized neuron by neuron, the same way they've been
+/// before switching to the weight matrix - so that older snapshots can
+/// still be loaded.
+#[derive(Serialize)]
+struct LayerRef<'a> {
+    neurons: Vec<NeuronRef<'a>>,
+    activation: Activation,
+    recurrent: bool,
+}
+
+#[derive(Deserialize)]
+struct LayerRepr {
+    neurons: Vec<Neuron>,
+    activation: Activation,
+    #[serde(default)]
+    recurrent: bool,
+}
+
+impl Serialize for Layer {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        LayerRef {
+            neurons: self.neurons().collect(),
+            activation: self.activation,
+            recurrent: self.recurrent,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Layer {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let layer = LayerRepr::deserialize(deserializer)?;
+
+        let matrix = Self::try_new(layer.neurons, layer.activation).map_err(de::Error::custom)?;
+
+        if layer.recurrent {
+            matrix.try_with_recurrence().map_err(de::Error::custom)
+        } else {
+            Ok(matrix)
+        }
     }
 }
 
@@ -56,16 +325,12 @@ mod tests {
         #[test]
         fn test() {
             let mut rng = ChaCha8Rng::from_seed(Default::default());
-            let layer = Layer::random(&mut rng, 3, 2);
+            let layer = Layer::random(&mut rng, 3, 2, Activation::Relu);
 
-            let actual_biases: Vec<_> = layer.neurons.iter().map(|neuron| neuron.bias).collect();
+            let actual_biases: Vec<_> = layer.neurons().map(|neuron| neuron.bias).collect();
             let expected_biases = vec![-0.6255188, 0.5238807];
 
-            let actual_weights: Vec<_> = layer
-                .neurons
-                .iter()
-                .map(|neuron| neuron.weights.as_slice())
-                .collect();
+            let actual_weights: Vec<_> = layer.neurons().map(|neuron| neuron.weights).collect();
             let expected_weights: Vec<&[f32]> = vec![
                 &[0.67383957, 0.8181262, 0.26284897],
                 &[-0.53516835, 0.069369674, -0.7648182],
@@ -85,17 +350,164 @@ mod tests {
                 Neuron::new(0.0, vec![0.1, 0.2, 0.3]),
                 Neuron::new(0.0, vec![0.4, 0.5, 0.6]),
             );
-            let layer = Layer::new(vec![neurons.0.clone(), neurons.1.clone()]);
+            let layer = Layer::new(
+                vec![neurons.0.clone(), neurons.1.clone()],
+                Activation::Tanh,
+            );
 
             let inputs = &[-0.5, 0.0, 0.5];
 
             let actual = layer.propagate(inputs.to_vec());
-            let expected = vec![neurons.0.propagate(inputs), neurons.1.propagate(inputs)];
+            let expected = vec![
+                neurons.0.propagate(inputs, Activation::Tanh),
+                neurons.1.propagate(inputs, Activation::Tanh),
+            ];
 
             approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
         }
     }
 
+    mod propagate_batch {
+        use super::*;
+        use rand::{Rng, SeedableRng};
+        use rand_chacha::ChaCha8Rng;
+
+        #[test]
+        fn test() {
+            let layer = Layer::new(
+                vec![
+                    Neuron::new(0.1, vec![0.1, 0.2, 0.3]),
+                    Neuron::new(-0.1, vec![0.4, 0.5, 0.6]),
+                ],
+                Activation::Tanh,
+            );
+
+            let inputs = [[-0.5, 0.0, 0.5], [1.0, 2.0, 3.0]];
+
+            let actual = layer.propagate_batch(&inputs.concat());
+
+            // tanh(0.1 - 0.05 + 0.15), tanh(-0.1 - 0.2 + 0.3),
+            // tanh(0.1 + 0.1 + 0.4 + 0.9), tanh(-0.1 + 0.4 + 1.0 + 1.8)
+            let expected = [0.19737533, 0.0, 0.9051482, 0.9959494];
+
+            approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+        }
+
+        #[test]
+        fn matches_neurons() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let layer = Layer::random(&mut rng, 5, 3, Activation::Sigmoid);
+
+            // More than one block, with the last one partially filled
+            let inputs: Vec<Vec<f32>> = (0..19)
+                .map(|_| (0..5).map(|_| rng.gen_range(-1.0..=1.0)).collect())
+                .collect();
+
+            let actual = layer.propagate_batch(&inputs.concat());
+
+            let expected: Vec<_> = inputs
+                .iter()
+                .flat_map(|inputs| {
+                    layer
+                        .neurons()
+                        .map(|neuron| neuron.propagate(inputs, Activation::Sigmoid))
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+
+            approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+        }
+
+        #[test]
+        fn skips_recurrent_weights() {
+            let layer = Layer::new(
+                vec![Neuron::new(0.5, vec![1.0, 2.0, 100.0])],
+                Activation::Identity,
+            )
+            .with_recurrence();
+
+            let actual = layer.propagate_batch(&[1.0, 2.0, 3.0, 4.0]);
+
+            approx::assert_relative_eq!(actual.as_slice(), [5.5, 11.5].as_slice());
+        }
+    }
+
+    mod try_with_recurrence {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let layer = |weights| Layer::new(vec![Neuron::new(0.0, weights)], Activation::Relu);
+
+            assert!(layer(vec![0.1, 0.2]).try_with_recurrence().is_ok());
+
+            assert_eq!(
+                layer(vec![0.1]).try_with_recurrence().err(),
+                Some(Error::InvalidRecurrence),
+            );
+        }
+    }
+
+    mod deserialize {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let layer = |recurrent| {
+                serde_json::from_value::<Layer>(serde_json::json!({
+                    "neurons": [{ "bias": 0.0, "weights": [0.1] }],
+                    "activation": "Relu",
+                    "recurrent": recurrent,
+                }))
+            };
+
+            assert!(layer(false).is_ok());
+            assert!(layer(true).is_err());
+        }
+    }
+
+    mod try_new {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let layer = |neurons| Layer::try_new(neurons, Activation::Relu).err();
+
+            assert_eq!(layer(vec![Neuron::new(0.0, vec![0.1])]), None);
+            assert_eq!(layer(Vec::new()), Some(Error::EmptyLayer));
+
+            assert_eq!(
+                layer(vec![Neuron::new(0.0, vec![0.1]), Neuron::new(0.0, vec![0.1, 0.2])]),
+                Some(Error::MismatchedWeights)
+            );
+        }
+    }
+
+    mod propagate_with_state {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let layer = Layer::new(
+                vec![
+                    Neuron::new(0.0, vec![1.0, 0.5, 0.0]),
+                    Neuron::new(0.0, vec![0.0, 0.0, -1.0]),
+                ],
+                Activation::Identity,
+            )
+            .with_recurrence();
+
+            let mut state = Vec::new();
+
+            let first = layer.propagate_with_state(vec![1.0], &mut state);
+            let second = layer.propagate_with_state(vec![1.0], &mut state);
+
+            approx::assert_relative_eq!(first.as_slice(), [1.0, 0.0].as_slice());
+            approx::assert_relative_eq!(second.as_slice(), [1.5, 0.0].as_slice());
+            approx::assert_relative_eq!(state.as_slice(), second.as_slice());
+        }
+    }
+
     mod from_weights {
         use super::*;
 
@@ -104,17 +516,14 @@ mod tests {
             let layer = Layer::from_weights(
                 3,
                 2,
+                Activation::Relu,
                 &mut vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8].into_iter(),
             );
 
-            let actual_biases: Vec<_> = layer.neurons.iter().map(|neuron| neuron.bias).collect();
+            let actual_biases: Vec<_> = layer.neurons().map(|neuron| neuron.bias).collect();
             let expected_biases = vec![0.1, 0.5];
 
-            let actual_weights: Vec<_> = layer
-                .neurons
-                .iter()
-                .map(|neuron| neuron.weights.as_slice())
-                .collect();
+            let actual_weights: Vec<_> = layer.neurons().map(|neuron| neuron.weights).collect();
             let expected_weights: Vec<&[f32]> = vec![&[0.2, 0.3, 0.4], &[0.6, 0.7, 0.8]];
 
             approx::assert_relative_eq!(actual_biases.as_slice(), expected_biases.as_slice());
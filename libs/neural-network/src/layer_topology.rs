@@ -1,4 +1,14 @@
-#[derive(Clone, Copy, Debug)]
+use crate::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct LayerTopology {
     pub neurons: usize,
+
+    /// Activation of this layer's neurons (ignored for the input layer)
+    pub activation: Activation,
+
+    /// Whether this layer gets its own previous outputs as additional
+    /// inputs (Elman-style); ignored for the input layer
+    #[serde(default)]
+    pub recurrent: bool,
 }
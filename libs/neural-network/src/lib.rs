@@ -1,17 +1,24 @@
 #![feature(array_methods)]
 #![feature(crate_visibility_modifier)]
 
-pub use self::layer_topology::*;
-
-use self::{layer::*, neuron::*};
+pub use self::{
+    activation::*, error::*, exported_network::*, graph::*, layer::*, layer_topology::*,
+    memory::*, neuron::*,
+};
 use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
 use std::iter::once;
 
+mod activation;
+mod error;
+mod exported_network;
+mod graph;
 mod layer;
 mod layer_topology;
+mod memory;
 mod neuron;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Network {
     layers: Vec<Layer>,
 }
@@ -22,45 +29,208 @@ impl Network {
     }
 
     pub fn random(rng: &mut dyn RngCore, layers: &[LayerTopology]) -> Self {
-        assert!(layers.len() > 1);
+        Self::try_random(rng, layers).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_random(rng: &mut dyn RngCore, layers: &[LayerTopology]) -> Result<Self, Error> {
+        Self::check_topology(layers)?;
 
         let layers = layers
             .windows(2)
-            .map(|layers| Layer::random(rng, layers[0].neurons, layers[1].neurons))
+            .map(|layers| {
+                let layer = Layer::random(
+                    rng,
+                    Self::input_size(layers),
+                    layers[1].neurons,
+                    layers[1].activation,
+                );
+
+                if layers[1].recurrent {
+                    layer.with_recurrence()
+                } else {
+                    layer
+                }
+            })
             .collect();
 
-        Self::new(layers)
+        Ok(Self::new(layers))
     }
 
     pub fn from_weights(layers: &[LayerTopology], weights: impl IntoIterator<Item = f32>) -> Self {
-        assert!(layers.len() > 1);
+        Self::try_from_weights(layers, weights).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_from_weights(
+        layers: &[LayerTopology],
+        weights: impl IntoIterator<Item = f32>,
+    ) -> Result<Self, Error> {
+        Self::check_topology(layers)?;
 
         let mut weights = weights.into_iter();
 
         let layers = layers
             .windows(2)
-            .map(|layers| Layer::from_weights(layers[0].neurons, layers[1].neurons, &mut weights))
-            .collect();
+            .map(|layers| {
+                let layer = Layer::try_from_weights(
+                    Self::input_size(layers),
+                    layers[1].neurons,
+                    layers[1].activation,
+                    &mut weights,
+                )?;
+
+                if layers[1].recurrent {
+                    layer.try_with_recurrence()
+                } else {
+                    Ok(layer)
+                }
+            })
+            .collect::<Result<_, _>>()?;
 
         if weights.next().is_some() {
-            panic!("got too many weights");
+            return Err(Error::TooManyWeights);
+        }
+
+        Ok(Self::new(layers))
+    }
+
+    /// Checks whether a network of given shape can be built at all - i.e.
+    /// whether it has at least two layers, none of them empty.
+    pub fn check_topology(layers: &[LayerTopology]) -> Result<(), Error> {
+        if layers.len() < 2 {
+            return Err(Error::NotEnoughLayers);
         }
 
-        Self::new(layers)
+        if layers.iter().any(|layer| layer.neurons == 0) {
+            return Err(Error::EmptyLayer);
+        }
+
+        Ok(())
     }
 
+    /// Propagates inputs through the network; recurrent layers (if
+    /// any) start from a blank memory each time.
     pub fn propagate(&self, inputs: Vec<f32>) -> Vec<f32> {
+        assert_eq!(inputs.len(), self.layers[0].outer_input_size());
+
+        self.layers
+            .iter()
+            .fold(inputs, |inputs, layer| layer.propagate_blank(&inputs))
+    }
+
+    /// Same as `propagate()`, but recurrent layers remember their
+    /// outputs in `memory`, to be fed back during the next call.
+    pub fn propagate_with_memory(&self, inputs: Vec<f32>, memory: &mut Memory) -> Vec<f32> {
+        if memory.states.len() != self.layers.len() {
+            memory.states = vec![Vec::new(); self.layers.len()];
+        }
+
         self.layers
             .iter()
-            .fold(inputs, |inputs, layer| layer.propagate(inputs))
+            .zip(&mut memory.states)
+            .fold(inputs, |inputs, (layer, state)| layer.propagate_with_state(inputs, state))
+    }
+
+    /// Same as `propagate_with_memory()`, but returns outputs of each
+    /// layer (the last one being network's output) - e.g. to visualize
+    /// what's going on inside the network.
+    pub fn trace_with_memory(&self, inputs: Vec<f32>, memory: &mut Memory) -> Vec<Vec<f32>> {
+        if memory.states.len() != self.layers.len() {
+            memory.states = vec![Vec::new(); self.layers.len()];
+        }
+
+        let mut inputs = inputs;
+        let mut outputs = Vec::with_capacity(self.layers.len());
+
+        for (layer, state) in self.layers.iter().zip(&mut memory.states) {
+            inputs = layer.propagate_with_state(inputs, state);
+            outputs.push(inputs.clone());
+        }
+
+        outputs
+    }
+
+    /// Propagates many inputs at once, layer by layer, multiplying each
+    /// layer's weights by a block of inputs at a time.
+    ///
+    /// For batches of a hundred inputs or more, that's about 1.5x faster
+    /// than calling `propagate()` for each of them; for a handful, it's
+    /// slightly slower (see `benches/propagation.rs`).
+    ///
+    /// Just like with `propagate()`, recurrent layers (if any) start from
+    /// a blank memory for each input.
+    pub fn propagate_batch(&self, inputs: &[Vec<f32>]) -> Vec<Vec<f32>> {
+        let input_size = self.layers[0].outer_input_size();
+
+        assert!(inputs.iter().all(|inputs| inputs.len() == input_size));
+
+        if inputs.is_empty() {
+            return Vec::new();
+        }
+
+        let inputs: Vec<_> = inputs.iter().flatten().copied().collect();
+
+        let outputs = self
+            .layers
+            .iter()
+            .fold(inputs, |inputs, layer| layer.propagate_batch(&inputs));
+
+        let output_size = self.layers.last().unwrap().output_size();
+
+        outputs
+            .chunks_exact(output_size)
+            .map(|outputs| outputs.to_vec())
+            .collect()
+    }
+
+    pub fn layers(&self) -> &[Layer] {
+        &self.layers
     }
 
     pub fn weights(&self) -> impl Iterator<Item = f32> + '_ {
         self.layers
             .iter()
-            .flat_map(|layer| layer.neurons.iter())
-            .flat_map(|neuron| once(&neuron.bias).chain(&neuron.weights))
-            .cloned()
+            .flat_map(|layer| layer.neurons())
+            .flat_map(|neuron| once(neuron.bias).chain(neuron.weights.iter().copied()))
+    }
+
+    /// Returns shape of this network - the inverse of what it's been
+    /// created from.
+    pub fn topology(&self) -> Vec<LayerTopology> {
+        let input = LayerTopology {
+            neurons: self.layers[0].outer_input_size(),
+            activation: Activation::Identity,
+            recurrent: false,
+        };
+
+        once(input)
+            .chain(self.layers.iter().map(|layer| LayerTopology {
+                neurons: layer.output_size(),
+                activation: layer.activation(),
+                recurrent: layer.is_recurrent(),
+            }))
+            .collect()
+    }
+
+    pub fn export(&self) -> ExportedNetwork {
+        ExportedNetwork {
+            topology: self.topology(),
+            weights: self.weights().collect(),
+        }
+    }
+
+    /// Re-creates network out of what `export()` has returned; fails when
+    /// the weights don't match the topology.
+    pub fn try_import(exported: &ExportedNetwork) -> Result<Self, Error> {
+        Self::try_from_weights(&exported.topology, exported.weights.iter().copied())
+    }
+
+    /// Number of inputs each of the second layer's neurons has
+    fn input_size(layers: &[LayerTopology]) -> usize {
+        if layers[1].recurrent {
+            layers[0].neurons + layers[1].neurons
+        } else {
+            layers[0].neurons
+        }
     }
 }
 
@@ -80,44 +250,97 @@ mod tests {
             let network = Network::random(
                 &mut rng,
                 &[
-                    LayerTopology { neurons: 3 },
-                    LayerTopology { neurons: 2 },
-                    LayerTopology { neurons: 1 },
+                    LayerTopology { neurons: 3, activation: Activation::Relu, recurrent: false },
+                    LayerTopology { neurons: 2, activation: Activation::Relu, recurrent: false },
+                    LayerTopology { neurons: 1, activation: Activation::Relu, recurrent: false },
                 ],
             );
 
             assert_eq!(network.layers.len(), 2);
-            assert_eq!(network.layers[0].neurons.len(), 2);
+            assert_eq!(network.layers[0].output_size(), 2);
 
-            approx::assert_relative_eq!(network.layers[0].neurons[0].bias, -0.6255188);
+            approx::assert_relative_eq!(network.layers[0].neuron(0).bias, -0.6255188);
 
             approx::assert_relative_eq!(
-                network.layers[0].neurons[0].weights.as_slice(),
+                network.layers[0].neuron(0).weights,
                 &[0.67383957, 0.8181262, 0.26284897].as_slice()
             );
 
-            approx::assert_relative_eq!(network.layers[0].neurons[1].bias, 0.5238807);
+            approx::assert_relative_eq!(network.layers[0].neuron(1).bias, 0.5238807);
 
             approx::assert_relative_eq!(
-                network.layers[0].neurons[1].weights.as_slice(),
+                network.layers[0].neuron(1).weights,
                 &[-0.5351684, 0.069369555, -0.7648182].as_slice()
             );
 
-            assert_eq!(network.layers[1].neurons.len(), 1);
+            assert_eq!(network.layers[1].output_size(), 1);
 
             approx::assert_relative_eq!(
-                network.layers[1].neurons[0].weights.as_slice(),
+                network.layers[1].neuron(0).weights,
                 &[-0.48879623, -0.19277143].as_slice()
             );
         }
     }
 
+    mod export {
+        use super::*;
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha8Rng;
+
+        fn topology() -> Vec<LayerTopology> {
+            vec![
+                LayerTopology { neurons: 3, activation: Activation::Identity, recurrent: false },
+                LayerTopology { neurons: 2, activation: Activation::Sigmoid, recurrent: true },
+                LayerTopology { neurons: 1, activation: Activation::Relu, recurrent: false },
+            ]
+        }
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let network = Network::random(&mut rng, &topology());
+            let exported = network.export();
+
+            assert_eq!(exported.topology, topology());
+            assert_eq!(exported.weights, network.weights().collect::<Vec<_>>());
+
+            let imported = Network::try_import(&exported).unwrap();
+
+            assert_eq!(imported.export(), exported);
+
+            let mut memory = Memory::default();
+            let mut imported_memory = Memory::default();
+
+            for inputs in &[[0.5, -0.5, 1.0], [1.0, 0.25, 0.0]] {
+                let actual = imported.propagate_with_memory(inputs.to_vec(), &mut imported_memory);
+                let expected = network.propagate_with_memory(inputs.to_vec(), &mut memory);
+
+                approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+            }
+        }
+
+        #[test]
+        fn rejects_mismatched_weights() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut exported = Network::random(&mut rng, &topology()).export();
+
+            exported.weights.push(0.5);
+            assert_eq!(Network::try_import(&exported).err(), Some(Error::TooManyWeights));
+
+            exported.weights.truncate(3);
+            assert_eq!(Network::try_import(&exported).err(), Some(Error::NotEnoughWeights));
+        }
+    }
+
     mod from_weights {
         use super::*;
 
         #[test]
         fn test() {
-            let layers = &[LayerTopology { neurons: 3 }, LayerTopology { neurons: 2 }];
+            let layers = &[
+                LayerTopology { neurons: 3, activation: Activation::Relu, recurrent: false },
+                LayerTopology { neurons: 2, activation: Activation::Relu, recurrent: false },
+            ];
             let weights = vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8];
 
             let actual: Vec<_> = Network::from_weights(layers, weights.clone())
@@ -126,6 +349,44 @@ mod tests {
 
             approx::assert_relative_eq!(actual.as_slice(), weights.as_slice());
         }
+
+        #[test]
+        fn given_wrong_number_of_weights() {
+            let layers = &[
+                LayerTopology { neurons: 3, activation: Activation::Relu, recurrent: false },
+                LayerTopology { neurons: 2, activation: Activation::Relu, recurrent: false },
+            ];
+
+            let actual = |weights: usize| Network::try_from_weights(layers, vec![0.0; weights]).err();
+
+            assert_eq!(actual(8), None);
+            assert_eq!(actual(7), Some(Error::NotEnoughWeights));
+            assert_eq!(actual(9), Some(Error::TooManyWeights));
+        }
+
+        #[test]
+        fn given_wrong_topology() {
+            let layer = |neurons| LayerTopology { neurons, activation: Activation::Relu, recurrent: false };
+
+            let actual = |layers: &[LayerTopology]| Network::try_from_weights(layers, Vec::new()).err();
+
+            assert_eq!(actual(&[layer(3)]), Some(Error::NotEnoughLayers));
+            assert_eq!(actual(&[layer(3), layer(0)]), Some(Error::EmptyLayer));
+        }
+
+        #[test]
+        fn assigns_activations() {
+            let layers = &[
+                LayerTopology { neurons: 2, activation: Activation::Step, recurrent: false },
+                LayerTopology { neurons: 2, activation: Activation::Sigmoid, recurrent: false },
+                LayerTopology { neurons: 1, activation: Activation::Tanh, recurrent: false },
+            ];
+
+            let network = Network::from_weights(layers, vec![0.0; 9]);
+
+            assert_eq!(network.layers[0].activation, Activation::Sigmoid);
+            assert_eq!(network.layers[1].activation, Activation::Tanh);
+        }
     }
 
     mod propagate {
@@ -134,11 +395,14 @@ mod tests {
         #[test]
         fn test() {
             let layers = (
-                Layer::new(vec![
-                    Neuron::new(0.0, vec![-0.5, -0.4, -0.3]),
-                    Neuron::new(0.0, vec![-0.2, -0.1, 0.0]),
-                ]),
-                Layer::new(vec![Neuron::new(0.0, vec![-0.5, 0.5])]),
+                Layer::new(
+                    vec![
+                        Neuron::new(0.0, vec![-0.5, -0.4, -0.3]),
+                        Neuron::new(0.0, vec![-0.2, -0.1, 0.0]),
+                    ],
+                    Activation::Relu,
+                ),
+                Layer::new(vec![Neuron::new(0.0, vec![-0.5, 0.5])], Activation::Tanh),
             );
             let network = Network::new(vec![layers.0.clone(), layers.1.clone()]);
 
@@ -149,14 +413,164 @@ mod tests {
         }
     }
 
+    mod propagate_with_memory {
+        use super::*;
+
+        /// Network with a single recurrent neuron that adds half of its
+        /// previous output to its current input
+        fn network() -> Network {
+            let layers = &[
+                LayerTopology { neurons: 1, activation: Activation::Identity, recurrent: false },
+                LayerTopology { neurons: 1, activation: Activation::Identity, recurrent: true },
+            ];
+
+            Network::from_weights(layers, vec![0.0, 1.0, 0.5])
+        }
+
+        #[test]
+        fn remembers_previous_outputs() {
+            let network = network();
+            let mut memory = Memory::default();
+
+            let actual: Vec<_> = [1.0, 0.0, 0.0, 2.0]
+                .iter()
+                .map(|&input| network.propagate_with_memory(vec![input], &mut memory)[0])
+                .collect();
+
+            approx::assert_relative_eq!(actual.as_slice(), [1.0, 0.5, 0.25, 2.125].as_slice());
+        }
+
+        #[test]
+        fn starts_from_blank_memory() {
+            let network = network();
+            let mut memory = Memory::default();
+
+            network.propagate_with_memory(vec![1.0], &mut memory);
+            memory.reset();
+
+            approx::assert_relative_eq!(
+                network.propagate_with_memory(vec![0.0], &mut memory)[0],
+                0.0
+            );
+
+            // `propagate()` doesn't remember anything at all
+            network.propagate(vec![1.0]);
+            approx::assert_relative_eq!(network.propagate(vec![0.0])[0], 0.0);
+        }
+
+        #[test]
+        fn weights_include_recurrent_ones() {
+            let layers = &[
+                LayerTopology { neurons: 2, activation: Activation::Relu, recurrent: false },
+                LayerTopology { neurons: 3, activation: Activation::Relu, recurrent: true },
+                LayerTopology { neurons: 1, activation: Activation::Relu, recurrent: false },
+            ];
+
+            // 3 * (1 + 2 + 3) for the recurrent layer, 1 + 3 for the output
+            let weights: Vec<_> = (0..22).map(|weight| weight as f32).collect();
+
+            let actual: Vec<_> = Network::from_weights(layers, weights.clone())
+                .weights()
+                .collect();
+
+            approx::assert_relative_eq!(actual.as_slice(), weights.as_slice());
+        }
+    }
+
+    mod trace_with_memory {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let layers = (
+                Layer::new(
+                    vec![
+                        Neuron::new(0.0, vec![-0.5, -0.4, -0.3]),
+                        Neuron::new(0.0, vec![-0.2, -0.1, 0.0]),
+                    ],
+                    Activation::Relu,
+                ),
+                Layer::new(vec![Neuron::new(0.0, vec![-0.5, 0.5])], Activation::Tanh),
+            );
+            let network = Network::new(vec![layers.0.clone(), layers.1.clone()]);
+
+            let actual = network.trace_with_memory(vec![0.5, 0.6, 0.7], &mut Memory::default());
+
+            let hidden = layers.0.propagate(vec![0.5, 0.6, 0.7]);
+            let output = layers.1.propagate(hidden.clone());
+
+            assert_eq!(actual.len(), 2);
+            approx::assert_relative_eq!(actual[0].as_slice(), hidden.as_slice());
+            approx::assert_relative_eq!(actual[1].as_slice(), output.as_slice());
+        }
+    }
+
+    mod propagate_batch {
+        use super::*;
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha8Rng;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let network = Network::random(
+                &mut rng,
+                &[
+                    LayerTopology { neurons: 3, activation: Activation::Identity, recurrent: false },
+                    LayerTopology { neurons: 4, activation: Activation::Relu, recurrent: true },
+                    LayerTopology { neurons: 2, activation: Activation::Sigmoid, recurrent: false },
+                ],
+            );
+
+            let inputs: Vec<_> = (0..5)
+                .map(|_| (0..3).map(|_| rng.gen_range(-1.0..=1.0)).collect::<Vec<_>>())
+                .collect();
+
+            let actual = network.propagate_batch(&inputs);
+
+            // Computed neuron by neuron, with blank memory fed to the
+            // recurrent layer explicitly
+            let expected: Vec<_> = inputs
+                .iter()
+                .map(|inputs| {
+                    network.layers().iter().fold(inputs.clone(), |mut inputs, layer| {
+                        inputs.resize(layer.input_size(), 0.0);
+
+                        layer
+                            .neurons()
+                            .map(|neuron| neuron.propagate(&inputs, layer.activation()))
+                            .collect()
+                    })
+                })
+                .collect();
+
+            assert_eq!(actual.len(), expected.len());
+
+            for (actual, expected) in actual.iter().zip(&expected) {
+                approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+            }
+        }
+
+        #[test]
+        fn empty_batch() {
+            let network = Network::new(vec![Layer::new(
+                vec![Neuron::new(0.0, vec![1.0])],
+                Activation::Relu,
+            )]);
+
+            assert!(network.propagate_batch(&[]).is_empty());
+        }
+    }
+
     mod weights {
         use super::*;
 
         #[test]
         fn test() {
             let network = Network::new(vec![
-                Layer::new(vec![Neuron::new(0.1, vec![0.2, 0.3, 0.4])]),
-                Layer::new(vec![Neuron::new(0.5, vec![0.6, 0.7, 0.8])]),
+                Layer::new(vec![Neuron::new(0.1, vec![0.2, 0.3, 0.4])], Activation::Relu),
+                Layer::new(vec![Neuron::new(0.5, vec![0.6, 0.7, 0.8])], Activation::Relu),
             ]);
 
             let actual: Vec<_> = network.weights().collect();
@@ -0,0 +1,18 @@
+use crate::*;
+
+/// Hidden state of network's recurrent layers, carried over from one
+/// `Network::propagate_with_memory()` call to the next one.
+///
+/// Fresh (or `reset()`) memory behaves as if the recurrent layers have
+/// seen only zeros so far.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Memory {
+    /// Previous outputs of each layer (empty for the non-recurrent ones)
+    crate states: Vec<Vec<f32>>,
+}
+
+impl Memory {
+    pub fn reset(&mut self) {
+        self.states.clear();
+    }
+}
@@ -1,16 +1,31 @@
 use crate::*;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Neuron {
     crate bias: f32,
     crate weights: Vec<f32>,
 }
 
+/// Neuron borrowed from its layer's weight matrix (see `Layer`).
+///
+/// Serializes the same way as `Neuron` does.
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct NeuronRef<'a> {
+    crate bias: f32,
+    crate weights: &'a [f32],
+}
+
 impl Neuron {
     pub fn new(bias: f32, weights: Vec<f32>) -> Self {
-        assert!(!weights.is_empty());
+        Self::try_new(bias, weights).unwrap_or_else(|err| panic!("{}", err))
+    }
 
-        Self { bias, weights }
+    pub fn try_new(bias: f32, weights: Vec<f32>) -> Result<Self, Error> {
+        if weights.is_empty() {
+            return Err(Error::NoWeights);
+        }
+
+        Ok(Self { bias, weights })
     }
 
     pub fn random(rng: &mut dyn RngCore, output_neurons: usize) -> Self {
@@ -24,25 +39,57 @@ impl Neuron {
     }
 
     pub fn from_weights(output_neurons: usize, weights: &mut dyn Iterator<Item = f32>) -> Self {
-        let bias = weights.next().expect("got not enough weights");
+        Self::try_from_weights(output_neurons, weights).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_from_weights(
+        output_neurons: usize,
+        weights: &mut dyn Iterator<Item = f32>,
+    ) -> Result<Self, Error> {
+        let bias = weights.next().ok_or(Error::NotEnoughWeights)?;
 
         let weights = (0..output_neurons)
-            .map(|_| weights.next().expect("got not enough weights"))
-            .collect();
+            .map(|_| weights.next().ok_or(Error::NotEnoughWeights))
+            .collect::<Result<_, _>>()?;
 
-        Self::new(bias, weights)
+        Self::try_new(bias, weights)
+    }
+
+    pub fn propagate(&self, inputs: &[f32], activation: Activation) -> f32 {
+        self.view().propagate(inputs, activation)
+    }
+
+    pub fn view(&self) -> NeuronRef<'_> {
+        NeuronRef {
+            bias: self.bias,
+            weights: &self.weights,
+        }
+    }
+}
+
+impl NeuronRef<'_> {
+    pub fn bias(&self) -> f32 {
+        self.bias
     }
 
-    pub fn propagate(&self, inputs: &[f32]) -> f32 {
+    pub fn weights(&self) -> &[f32] {
+        self.weights
+    }
+
+    pub fn propagate(&self, inputs: &[f32], activation: Activation) -> f32 {
         assert_eq!(inputs.len(), self.weights.len());
 
         let output = inputs
             .iter()
-            .zip(&self.weights)
+            .zip(self.weights)
             .map(|(input, weight)| input * weight)
             .sum::<f32>();
 
-        (self.bias + output).max(0.0)
+        activation.apply(self.bias + output)
+    }
+
+    pub fn to_neuron(&self) -> Neuron {
+        Neuron::new(self.bias, self.weights.to_vec())
     }
 }
 
@@ -74,7 +121,8 @@ mod tests {
 
         #[test]
         fn returns_propagated_input() {
-            let actual = Neuron::new(0.1, vec![-0.3, 0.6, 0.9]).propagate(&[0.5, -0.6, 0.7]);
+            let actual = Neuron::new(0.1, vec![-0.3, 0.6, 0.9])
+                .propagate(&[0.5, -0.6, 0.7], Activation::Relu);
             let expected: f32 = 0.1 + (0.5 * -0.3) + (-0.6 * 0.6) + (0.7 * 0.9);
 
             approx::assert_relative_eq!(actual, expected.max(0.0));
@@ -83,17 +131,60 @@ mod tests {
         #[test]
         fn restricts_output() {
             let neuron = Neuron::new(0.0, vec![0.5]);
-            let v1 = neuron.propagate(&[-1.0]);
-            let v2 = neuron.propagate(&[-0.5]);
-            let v3 = neuron.propagate(&[0.0]);
-            let v4 = neuron.propagate(&[0.5]);
-            let v5 = neuron.propagate(&[1.0]);
+            let v1 = neuron.propagate(&[-1.0], Activation::Relu);
+            let v2 = neuron.propagate(&[-0.5], Activation::Relu);
+            let v3 = neuron.propagate(&[0.0], Activation::Relu);
+            let v4 = neuron.propagate(&[0.5], Activation::Relu);
+            let v5 = neuron.propagate(&[1.0], Activation::Relu);
 
             approx::assert_relative_eq!(v1, v2);
             approx::assert_relative_eq!(v2, v3);
             approx::assert_relative_ne!(v3, v4);
             approx::assert_relative_ne!(v4, v5);
         }
+
+        mod given_other_activations {
+            use super::*;
+
+            fn actual(activation: Activation) -> f32 {
+                Neuron::new(0.1, vec![-0.3, 0.6, 0.9]).propagate(&[0.5, -0.6, 0.7], activation)
+            }
+
+            // Same neuron as above, but with an input sum that's negative:
+            fn actual_negative(activation: Activation) -> f32 {
+                Neuron::new(-0.5, vec![0.5]).propagate(&[-1.0], activation)
+            }
+
+            #[test]
+            fn leaky_relu() {
+                approx::assert_relative_eq!(actual(Activation::LeakyRelu), 0.22);
+                approx::assert_relative_eq!(actual_negative(Activation::LeakyRelu), -0.01);
+            }
+
+            #[test]
+            fn sigmoid() {
+                approx::assert_relative_eq!(actual(Activation::Sigmoid), 0.55477923);
+                approx::assert_relative_eq!(actual_negative(Activation::Sigmoid), 0.26894143);
+            }
+
+            #[test]
+            fn tanh() {
+                approx::assert_relative_eq!(actual(Activation::Tanh), 0.21651806);
+                approx::assert_relative_eq!(actual_negative(Activation::Tanh), -0.7615942);
+            }
+
+            #[test]
+            fn identity() {
+                approx::assert_relative_eq!(actual(Activation::Identity), 0.22);
+                approx::assert_relative_eq!(actual_negative(Activation::Identity), -1.0);
+            }
+
+            #[test]
+            fn step() {
+                approx::assert_relative_eq!(actual(Activation::Step), 1.0);
+                approx::assert_relative_eq!(actual_negative(Activation::Step), 0.0);
+            }
+        }
     }
 
     mod from_weights {
@@ -107,5 +198,22 @@ mod tests {
             approx::assert_relative_eq!(actual.bias, expected.bias);
             approx::assert_relative_eq!(actual.weights.as_slice(), expected.weights.as_slice());
         }
+
+        #[test]
+        fn given_not_enough_weights() {
+            let actual = Neuron::try_from_weights(3, &mut vec![0.1, 0.2, 0.3].into_iter());
+
+            assert_eq!(actual.err(), Some(Error::NotEnoughWeights));
+        }
+    }
+
+    mod try_new {
+        use super::*;
+
+        #[test]
+        fn test() {
+            assert!(Neuron::try_new(0.1, vec![0.2]).is_ok());
+            assert_eq!(Neuron::try_new(0.1, Vec::new()).err(), Some(Error::NoWeights));
+        }
     }
 }
@@ -0,0 +1,104 @@
+use lib_simulation as sim;
+use rand::prelude::*;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+/// Runs the simulation headlessly, at full speed, for a given number of
+/// generations.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "simulation-cli")]
+struct Args {
+    /// Number of generations to simulate
+    #[structopt(short, long, default_value = "100")]
+    generations: usize,
+
+    /// Seed of the run; when omitted, a random one is picked (and printed)
+    #[structopt(short, long)]
+    seed: Option<u64>,
+
+    /// JSON file with the simulation's config; missing fields fall back
+    /// to their defaults
+    #[structopt(short, long, parse(from_os_str))]
+    config: Option<PathBuf>,
+
+    /// Directory where checkpoints and the final snapshot are written
+    #[structopt(short, long, parse(from_os_str), default_value = "output")]
+    output: PathBuf,
+
+    /// Write a checkpoint every this many generations (0 = never)
+    #[structopt(long, default_value = "0")]
+    checkpoint_interval: usize,
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = Args::from_args();
+
+    let config = match &args.config {
+        Some(path) => serde_json::from_str(&fs::read_to_string(path)?)?,
+        None => sim::Config::default(),
+    };
+
+    let seed = args.seed.unwrap_or_else(|| thread_rng().gen());
+    let mut simulation = sim::Simulation::try_from_seed(seed, config)?;
+
+    fs::create_dir_all(&args.output)?;
+
+    println!("seed: {}", seed);
+    println!(
+        "generation\tmin\tmax\tmean\tmedian\tstd_dev\tfood_eaten\t\
+         predator_min\tpredator_max\tpredator_mean\tpredator_median\t\
+         predator_std_dev\tprey_caught"
+    );
+
+    // Driven by the simulation's own counter, since in continuous
+    // evolution a generation can end without any prey to report on
+    while simulation.generation() < args.generations {
+        let generation = simulation.generation();
+
+        match simulation.train() {
+            Some(stats) => println!("{}", format_stats(&stats)),
+            None => println!("{}\t{}", generation, ["-"; 12].join("\t")),
+        }
+
+        let completed = simulation.generation();
+
+        if args.checkpoint_interval > 0 && completed % args.checkpoint_interval == 0 {
+            let path = args.output.join(format!("checkpoint-{:06}.json", completed));
+
+            fs::write(path, simulation.to_json()?)?;
+        }
+    }
+
+    fs::write(args.output.join("final.json"), simulation.to_json()?)?;
+
+    Ok(())
+}
+
+fn format_stats(stats: &sim::GenerationStatistics) -> String {
+    let predators = match &stats.predators {
+        Some(predators) => format!(
+            "{:.2}\t{:.2}\t{:.2}\t{:.2}\t{:.2}\t{}",
+            predators.min_fitness,
+            predators.max_fitness,
+            predators.mean_fitness,
+            predators.median_fitness,
+            predators.std_dev_fitness,
+            predators.prey_caught,
+        ),
+        None => ["-"; 6].join("\t"),
+    };
+
+    format!(
+        "{}\t{:.2}\t{:.2}\t{:.2}\t{:.2}\t{:.2}\t{}\t{}",
+        stats.generation,
+        stats.min_fitness,
+        stats.max_fitness,
+        stats.mean_fitness,
+        stats.median_fitness,
+        stats.std_dev_fitness,
+        stats.food_eaten,
+        predators,
+    )
+}
@@ -5,8 +5,11 @@ use serde::Serialize;
 
 #[wasm_bindgen]
 pub struct Simulation {
-    rng: ThreadRng,
-    sim: sim::Simulation
+    sim: sim::Simulation,
+
+    /// Flat copy of the world, refilled by `updateWorldBuffer()` - see
+    /// `WorldBuffer` for its layout
+    buffer: Vec<f32>,
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -20,6 +23,7 @@ pub struct Animal {
     pub x: f32,
     pub y: f32,
     pub rotation: f32,
+    pub predator: bool,
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -28,24 +32,234 @@ pub struct Food {
     pub y: f32,
 }
 
+/// Layout of the world buffer - a faster, serde-less alternative to
+/// `world()` that JS reads straight out of wasm's memory:
+///
+/// ```text
+/// [ x, y, rotation, predator (0.0 or 1.0) ] * worldAnimals()
+/// [ x, y ] * worldFoods()
+/// ```
+struct WorldBuffer;
+
+impl WorldBuffer {
+    const ANIMAL_STRIDE: usize = 4;
+    const FOOD_STRIDE: usize = 2;
+
+    fn fill(buffer: &mut Vec<f32>, world: &sim::World) {
+        buffer.clear();
+
+        buffer.reserve(
+            world.animals().len() * Self::ANIMAL_STRIDE + world.foods().len() * Self::FOOD_STRIDE,
+        );
+
+        for animal in world.animals() {
+            buffer.extend_from_slice(&[
+                animal.position().x,
+                animal.position().y,
+                animal.rotation().angle(),
+                if animal.species() == sim::Species::Predator { 1.0 } else { 0.0 },
+            ]);
+        }
+
+        for food in world.foods() {
+            buffer.extend_from_slice(&[food.position().x, food.position().y]);
+        }
+    }
+}
+
 #[wasm_bindgen]
 impl Simulation {
+    /// Creates a simulation out of given config object (e.g.
+    /// `{ world_animals: 20, eye_cells: 5 }`); fields that are missing
+    /// fall back to their defaults, and so does the entire config when
+    /// it's not given at all.
+    ///
+    /// Throws when the config can't be deserialized or is invalid.
     #[wasm_bindgen(constructor)]
-    pub fn new() -> Self {
-        let mut rng = thread_rng();
-        let sim = sim::Simulation::random(&mut rng);
+    pub fn new(config: &JsValue) -> Result<Simulation, JsValue> {
+        let sim = sim::Simulation::try_random(parse_config(config)?, &mut thread_rng())
+            .map_err(|err| JsValue::from_str(&err.to_string()))?;
+
+        Ok(Self::from(sim))
+    }
+
+    /// Creates a reproducible simulation - the same seed (and config)
+    /// always yields the same run; config is handled the same way as by
+    /// the constructor.
+    #[wasm_bindgen(js_name = newWithSeed)]
+    pub fn new_with_seed(seed: u64, config: &JsValue) -> Result<Simulation, JsValue> {
+        let sim = sim::Simulation::try_from_seed(seed, parse_config(config)?)
+            .map_err(|err| JsValue::from_str(&err.to_string()))?;
+
+        Ok(Self::from(sim))
+    }
+
+    /// Starts over with a fresh, random population, using given config
+    /// object (see the constructor).
+    ///
+    /// Throws when the config can't be deserialized or is invalid - the
+    /// current simulation is left intact then.
+    pub fn reset(&mut self, config: &JsValue) -> Result<(), JsValue> {
+        *self = Self::new(config)?;
+
+        Ok(())
+    }
 
-        Self { rng, sim }
+    /// Returns the config this simulation is running with, with all of
+    /// its fields filled in.
+    pub fn config(&self) -> JsValue {
+        JsValue::from_serde(self.sim.config()).unwrap()
     }
 
+    /// Restores a simulation previously saved with `snapshot()`.
+    #[wasm_bindgen(js_name = fromSnapshot)]
+    pub fn from_snapshot(snapshot: &str) -> Result<Simulation, JsValue> {
+        let sim = sim::Simulation::from_json(snapshot)
+            .map_err(|err| JsValue::from_str(&err.to_string()))?;
+
+        Ok(Self::from(sim))
+    }
+
+    /// Serializes the entire simulation (including its random number
+    /// generator) into JSON, so that it can be resumed later.
+    pub fn snapshot(&self) -> String {
+        self.sim.to_json().unwrap()
+    }
+
+    /// Returns the world as a plain JS object; it's handy for debugging,
+    /// but - for drawing each frame - `updateWorldBuffer()` is faster.
     pub fn world(&self) -> JsValue {
         let world = World::from(self.sim.world());
         JsValue::from_serde(&world).unwrap()
     }
 
-    pub fn step(&mut self) {
-        self.sim.step(&mut self.rng);
+    /// Copies positions and rotations of all the animals and foods into
+    /// the world buffer; afterwards, JS can view it as
+    /// `new Float32Array(memory.buffer, worldBufferPtr(), worldBufferLen())`.
+    ///
+    /// The view has to be re-created after each update, since wasm's
+    /// memory might've grown (and moved) in the meantime.
+    #[wasm_bindgen(js_name = updateWorldBuffer)]
+    pub fn update_world_buffer(&mut self) {
+        WorldBuffer::fill(&mut self.buffer, self.sim.world());
+    }
+
+    #[wasm_bindgen(js_name = worldBufferPtr)]
+    pub fn world_buffer_ptr(&self) -> *const f32 {
+        self.buffer.as_ptr()
+    }
+
+    /// Number of floats (not bytes) in the world buffer.
+    #[wasm_bindgen(js_name = worldBufferLen)]
+    pub fn world_buffer_len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Number of animals in the world - and, as long as the simulation
+    /// hasn't been stepped since, in the world buffer.
+    #[wasm_bindgen(js_name = worldAnimals)]
+    pub fn world_animals(&self) -> usize {
+        self.sim.world().animals().len()
     }
+
+    /// Number of foods in the world (see `worldAnimals()`).
+    #[wasm_bindgen(js_name = worldFoods)]
+    pub fn world_foods(&self) -> usize {
+        self.sim.world().foods().len()
+    }
+
+    /// Exports brain of the animal at given index (as in
+    /// `world().animals`) into JSON; `undefined` if there's no such
+    /// animal.
+    #[wasm_bindgen(js_name = exportBrain)]
+    pub fn export_brain(&self, index: usize) -> Option<String> {
+        Some(self.sim.export_brain(index)?.to_json().unwrap())
+    }
+
+    /// Exports brain of the currently fittest prey (or predator) into
+    /// JSON; `undefined` if there are no animals of that species.
+    #[wasm_bindgen(js_name = exportFittestBrain)]
+    pub fn export_fittest_brain(&self, predator: bool) -> Option<String> {
+        let species = if predator {
+            sim::Species::Predator
+        } else {
+            sim::Species::Prey
+        };
+
+        Some(self.sim.export_fittest_brain(species)?.to_json().unwrap())
+    }
+
+    /// Puts a brain exported with `exportBrain()` (possibly by another
+    /// simulation) into the world, in place of the least fit animal of
+    /// its species; returns index of the animal that's got it.
+    ///
+    /// Throws when the brain can't be deserialized or doesn't fit this
+    /// simulation's animals.
+    #[wasm_bindgen(js_name = importBrain)]
+    pub fn import_brain(&mut self, brain: &str) -> Result<usize, JsValue> {
+        let brain = sim::ExportedBrain::from_json(brain)
+            .map_err(|err| JsValue::from_str(&err.to_string()))?;
+
+        self.sim
+            .try_import_brain(&brain)
+            .map_err(|err| JsValue::from_str(&err.to_string()))
+    }
+
+    /// Returns what the animal at given index (as in `world().animals`)
+    /// currently sees and thinks - its vision, hidden activations, brain
+    /// outputs, speed and satiation; `null` if there's no such animal.
+    #[wasm_bindgen(js_name = animalDetails)]
+    pub fn animal_details(&self, index: usize) -> JsValue {
+        match self.sim.animal_details(index) {
+            Some(details) => JsValue::from_serde(&details).unwrap(),
+            None => JsValue::NULL,
+        }
+    }
+
+    /// Performs a single step of the simulation; when that step ends
+    /// a generation, returns its statistics (otherwise `null`).
+    pub fn step(&mut self) -> JsValue {
+        match self.sim.step() {
+            Some(stats) => JsValue::from_serde(&stats).unwrap(),
+            None => JsValue::NULL,
+        }
+    }
+
+    /// Steps through the rest of the current generation without
+    /// rendering anything, returning its statistics (or `null` when
+    /// there's no prey left to report on).
+    pub fn train(&mut self) -> JsValue {
+        match self.sim.train() {
+            Some(stats) => JsValue::from_serde(&stats).unwrap(),
+            None => JsValue::NULL,
+        }
+    }
+
+    /// Same as `train()`, but goes through `n` generations at once,
+    /// returning an array of their statistics.
+    #[wasm_bindgen(js_name = trainGenerations)]
+    pub fn train_generations(&mut self, n: usize) -> JsValue {
+        JsValue::from_serde(&self.sim.train_generations(n)).unwrap()
+    }
+}
+
+impl From<sim::Simulation> for Simulation {
+    fn from(sim: sim::Simulation) -> Self {
+        Self {
+            sim,
+            buffer: Vec::new(),
+        }
+    }
+}
+
+fn parse_config(config: &JsValue) -> Result<sim::Config, JsValue> {
+    if config.is_undefined() || config.is_null() {
+        return Ok(Default::default());
+    }
+
+    config
+        .into_serde()
+        .map_err(|err| JsValue::from_str(&format!("invalid config: {}", err)))
 }
 
 impl From<&sim::World> for World {
@@ -71,6 +285,7 @@ impl From<&sim::Animal> for Animal {
             x: animal.position().x,
             y: animal.position().y,
             rotation: animal.rotation().angle(),
+            predator: animal.species() == sim::Species::Predator,
         }
     }
 }
@@ -0,0 +1,110 @@
+//! Compares brute-force collision & vision queries against the ones
+//! going through `Grid`:
+//!
+//! ```shell
+//! $ cargo bench -p lib-simulation
+//! ```
+//!
+//! Note that with the default, rather long `eye_fov_range` a good part
+//! of the world is within sight anyway, so vision gains much less than
+//! collisions do.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use lib_simulation::*;
+use nalgebra as na;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+const SIZES: [usize; 3] = [100, 1000, 5000];
+
+struct Fixture {
+    animals: Vec<(na::Point2<f32>, na::Rotation2<f32>)>,
+    foods: Vec<Food>,
+}
+
+impl Fixture {
+    fn new(size: usize) -> Self {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let animals = (0..size)
+            .map(|_| (rng.gen(), na::Rotation2::new(rng.gen())))
+            .collect();
+
+        let foods = (0..size).map(|_| Food::random(&mut rng)).collect();
+
+        Self { animals, foods }
+    }
+}
+
+fn collisions(c: &mut Criterion) {
+    let food_size = Config::default().food_size;
+    let mut group = c.benchmark_group("collisions");
+
+    for &size in &SIZES {
+        let fixture = Fixture::new(size);
+
+        group.bench_with_input(BenchmarkId::new("brute_force", size), &fixture, |b, fixture| {
+            b.iter(|| {
+                fixture
+                    .animals
+                    .iter()
+                    .flat_map(|(position, _)| {
+                        fixture.foods.iter().filter(move |food| {
+                            na::distance(position, &food.position()) <= food_size
+                        })
+                    })
+                    .count()
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("grid", size), &fixture, |b, fixture| {
+            b.iter(|| {
+                let grid = Grid::from_foods(&fixture.foods);
+
+                fixture
+                    .animals
+                    .iter()
+                    .flat_map(|(position, _)| {
+                        grid.query(*position, food_size).filter(move |&idx| {
+                            na::distance(position, &fixture.foods[idx].position()) <= food_size
+                        })
+                    })
+                    .count()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn vision(c: &mut Criterion) {
+    let eye = Eye::default();
+    let mut group = c.benchmark_group("vision");
+
+    for &size in &SIZES {
+        let fixture = Fixture::new(size);
+
+        group.bench_with_input(BenchmarkId::new("brute_force", size), &fixture, |b, fixture| {
+            b.iter(|| {
+                for (position, rotation) in &fixture.animals {
+                    eye.process_vision(*position, *rotation, &fixture.foods);
+                }
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("grid", size), &fixture, |b, fixture| {
+            b.iter(|| {
+                let grid = Grid::from_foods(&fixture.foods);
+
+                for (position, rotation) in &fixture.animals {
+                    eye.process_vision_near(*position, *rotation, &fixture.foods, &grid);
+                }
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, collisions, vision);
+criterion_main!(benches);
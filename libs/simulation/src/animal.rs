@@ -1,34 +1,81 @@
 use crate::*;
 use crate::eye::Eye;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Animal{
     crate position: na::Point2<f32>,
     crate rotation: na::Rotation2<f32>,
     crate speed: f32,
     crate eye: Eye,
     crate brain: Brain,
+    /// State of the brain's recurrent layer; blank when the animal is
+    /// born
+    #[serde(default)]
+    crate memory: nn::Memory,
     crate satiation: usize,
+    crate energy: f32,
+    /// Number of steps this animal has been alive for
+    crate lifetime: usize,
+    #[serde(default)]
+    crate species: Species,
+    /// Whether this animal has been caught by a predator
+    #[serde(default)]
+    crate caught: bool,
+}
+
+/// Snapshot of what's going on inside an animal's head - see
+/// `Simulation::animal_details()`.
+#[derive(Clone, Debug, Serialize)]
+pub struct AnimalDetails {
+    /// What the eye sees, channel after channel
+    pub vision: Vec<f32>,
+
+    /// Activations of the brain's hidden neurons
+    pub hidden: Vec<f32>,
+
+    /// Brain's raw outputs, before they get turned into speed and
+    /// rotation
+    pub outputs: Vec<f32>,
+
+    pub speed: f32,
+    pub satiation: usize,
 }
 
 impl Animal {
-    pub fn random(rng: &mut dyn RngCore) -> Self {
-        let eye = Eye::default();
-        let brain = Brain::random(rng, &eye);
+    pub fn random(config: &Config, rng: &mut dyn RngCore) -> Self {
+        Self::random_species(config, Species::Prey, rng)
+    }
+
+    pub fn random_species(config: &Config, species: Species, rng: &mut dyn RngCore) -> Self {
+        let eye = Eye::from_config(config);
+        let brain = Brain::random(config, species, rng);
         Self::new(
+            config,
+            species,
             eye,
             brain,
             rng)
     }
 
-    fn new(eye: Eye, brain: Brain, rng: &mut dyn RngCore) -> Self {
+    fn new(
+        config: &Config,
+        species: Species,
+        eye: Eye,
+        brain: Brain,
+        rng: &mut dyn RngCore,
+    ) -> Self {
         Self {
             position: rng.gen(),
             rotation: rng.gen(),
             speed: 0.002,
             eye,
             brain,
+            memory: nn::Memory::default(),
             satiation: 0,
+            energy: config.energy_initial,
+            lifetime: 0,
+            species,
+            caught: false,
         }
     }
 
@@ -51,14 +98,51 @@ impl Animal {
         self.rotation
     }
 
+    pub fn energy(&self) -> f32 {
+        self.energy
+    }
+
+    pub fn species(&self) -> Species {
+        self.species
+    }
+
+    crate fn is_alive(&self) -> bool {
+        self.energy > 0.0
+    }
+
     crate fn from_chromosome(
+        config: &Config,
+        species: Species,
         chromosome: ga::Chromosome,
         rng: &mut dyn RngCore,
     ) -> Self {
-        let eye = Eye::default();
-        let brain = Brain::from_chromosome(chromosome, &eye);
+        let eye = Eye::from_config(config);
+        let brain = Brain::from_chromosome(config, species, chromosome);
+
+        Self::new(config, species, eye, brain, rng)
+    }
+
+    crate fn from_genome(
+        config: &Config,
+        species: Species,
+        genome: ga::Genome,
+        rng: &mut dyn RngCore,
+    ) -> Self {
+        let eye = Eye::from_config(config);
+        let brain = Brain::from_genome(config, genome);
+
+        Self::new(config, species, eye, brain, rng)
+    }
+
+    crate fn from_brain(
+        config: &Config,
+        species: Species,
+        brain: Brain,
+        rng: &mut dyn RngCore,
+    ) -> Self {
+        let eye = Eye::from_config(config);
 
-        Self::new(eye, brain, rng)
+        Self::new(config, species, eye, brain, rng)
     }
 
     crate fn as_chromosome(&self) -> ga::Chromosome {
@@ -6,15 +6,15 @@ pub struct AnimalIndividual{
 }
 
 impl AnimalIndividual {
-    pub fn from_animal(animal: &Animal) -> Self {
+    pub fn from_animal(config: &Config, animal: &Animal) -> Self {
         Self {
-            fitness: animal.satiation as f32,
+            fitness: fitness(config, animal),
             chromosome: animal.as_chromosome(),
         }
     }
 
-    pub fn into_animal(self, rng: &mut dyn RngCore) -> Animal {
-        Animal::from_chromosome(self.chromosome, rng)
+    pub fn into_animal(self, config: &Config, species: Species, rng: &mut dyn RngCore) -> Animal {
+        Animal::from_chromosome(config, species, self.chromosome, rng)
     }
 
 }
@@ -34,4 +34,66 @@ impl ga::Individual for AnimalIndividual {
     fn fitness(&self) -> f32 {
         self.fitness
     }
-}
\ No newline at end of file
+}
+
+/// Counterpart of `AnimalIndividual` for `BrainKind::Neat`
+pub struct AnimalGenome {
+    fitness: f32,
+    genome: ga::Genome,
+}
+
+impl AnimalGenome {
+    pub fn from_animal(config: &Config, animal: &Animal) -> Self {
+        Self {
+            fitness: fitness(config, animal),
+            genome: animal
+                .brain
+                .genome()
+                .expect("got a layered brain while evolving NEAT ones")
+                .clone(),
+        }
+    }
+
+    pub fn into_animal(self, config: &Config, species: Species, rng: &mut dyn RngCore) -> Animal {
+        Animal::from_genome(config, species, self.genome, rng)
+    }
+}
+
+impl ga::NeatIndividual for AnimalGenome {
+    fn create(genome: ga::Genome) -> Self {
+        Self {
+            fitness: 0.0,
+            genome,
+        }
+    }
+
+    fn genome(&self) -> &ga::Genome {
+        &self.genome
+    }
+
+    fn fitness(&self) -> f32 {
+        self.fitness
+    }
+}
+
+/// Prey are rewarded for the food they've eaten and penalized for
+/// getting caught; predators are rewarded for the prey they've caught
+/// (which is what their satiation counts).
+crate fn fitness(config: &Config, animal: &Animal) -> f32 {
+    // Part of the generation this animal has managed to survive
+    let survival = (animal.lifetime as f32 / config.sim_generation_length as f32).min(1.0);
+
+    match animal.species {
+        Species::Prey => {
+            let fitness = animal.satiation as f32 + config.fitness_survival * survival;
+
+            if animal.caught {
+                (fitness - config.fitness_caught).max(0.0)
+            } else {
+                fitness
+            }
+        }
+
+        Species::Predator => animal.satiation as f32 + config.predator_fitness_survival * survival,
+    }
+}
@@ -1,31 +1,178 @@
 use crate::*;
+use serde::{de, Deserializer, Serializer};
 
 #[derive(Debug)]
 pub struct Brain {
     speed_accel: f32,
     rotation_accel: f32,
-    nn: nn::Network,
+    nn: Network,
+}
+
+#[derive(Debug)]
+enum Network {
+    Layered(nn::Network),
+    Neat(NeatNetwork),
+}
+
+/// Genome is kept along with the network built out of it, because -
+/// unlike with the layered networks - it can't be recovered from the
+/// network's weights alone
+#[derive(Debug, Serialize, Deserialize)]
+struct NeatNetwork {
+    genome: ga::Genome,
+    nn: nn::GraphNetwork,
 }
 
 impl Brain {
-    crate fn random(rng: &mut dyn RngCore) -> Self {
-        let nn = nn::Network::random(rng, &Self::topology(config));
+    /// Speed & rotation
+    crate const OUTPUTS: usize = 2;
 
-        Self::new(nn)
+    crate fn random(config: &Config, species: Species, rng: &mut dyn RngCore) -> Self {
+        match config.brain_kind {
+            BrainKind::Layered => {
+                let nn = nn::Network::random(rng, &Self::topology(config, species));
+
+                Self::new(config, Network::Layered(nn))
+            }
+
+            BrainKind::Neat => {
+                let inputs = Eye::from_config(config).inputs();
+
+                Self::from_genome(config, ga::Genome::minimal(rng, inputs, Self::OUTPUTS))
+            }
+        }
     }
 
-    crate fn from_chromosome(chromosome: ga::Chromosome) -> Self {
-        let nn = nn::Network::from_weights(&Self::topology(config), chromosome);
+    /// Checks whether brains of given species can be built out of given
+    /// config (assuming its eye is valid).
+    crate fn validate(config: &Config, species: Species) -> Result<(), nn::Error> {
+        match config.brain_kind {
+            BrainKind::Layered => nn::Network::check_topology(&Self::topology(config, species)),
 
-        Self::new(config, nn)
+            // NEAT genomes start without any hidden nodes, so as long as
+            // there are some inputs, there's nothing to go wrong
+            BrainKind::Neat => Ok(()),
+        }
     }
 
+    crate fn from_chromosome(
+        config: &Config,
+        species: Species,
+        chromosome: ga::Chromosome,
+    ) -> Self {
+        let nn = nn::Network::from_weights(&Self::topology(config, species), chromosome);
+
+        Self::new(config, Network::Layered(nn))
+    }
+
+    crate fn from_genome(config: &Config, genome: ga::Genome) -> Self {
+        let nn = Self::graph(config, &genome);
+
+        Self::new(config, Network::Neat(NeatNetwork { genome, nn }))
+    }
+
+    /// For NEAT brains, returns weights of their connections.
     crate fn as_chromosome(&self) -> ga::Chromosome {
-        self.nn.weights().collect()
+        match &self.nn {
+            Network::Layered(nn) => nn.weights().collect(),
+            Network::Neat(neat) => neat.genome.weights().collect(),
+        }
+    }
+
+    /// Returns `None` for the layered brains.
+    crate fn genome(&self) -> Option<&ga::Genome> {
+        match &self.nn {
+            Network::Layered(_) => None,
+            Network::Neat(neat) => Some(&neat.genome),
+        }
+    }
+
+    crate fn export(&self, config: &Config) -> ExportedBrainNetwork {
+        match &self.nn {
+            Network::Layered(nn) => ExportedBrainNetwork::Layered(nn.export()),
+
+            Network::Neat(neat) => ExportedBrainNetwork::Neat {
+                genome: neat.genome.clone(),
+                hidden_activation: config.brain_hidden_activation,
+                output_activation: config.brain_output_activation,
+            },
+        }
     }
 
-    crate fn propagate(&self, vision: Vec<f32>) -> (f32, f32) {
-        let response = self.nn.propagate(vision);
+    /// Re-creates an exported brain, making sure it's got exactly the
+    /// same shape as brains of given species in given config - otherwise
+    /// it couldn't evolve along with them.
+    ///
+    /// NEAT genomes get renumbered into `neat`'s history (the one brains
+    /// of given species evolve with), since innovation numbers handed out
+    /// by another simulation don't mean anything here.
+    crate fn try_import(
+        config: &Config,
+        species: Species,
+        network: &ExportedBrainNetwork,
+        neat: &mut ga::Neat,
+    ) -> Result<Self, Error> {
+        match (config.brain_kind, network) {
+            (BrainKind::Layered, ExportedBrainNetwork::Layered(exported)) => {
+                if exported.topology[..] != Self::topology(config, species)[..] {
+                    return Err(Error::IncompatibleBrain);
+                }
+
+                let nn = nn::Network::try_import(exported)?;
+
+                Ok(Self::new(config, Network::Layered(nn)))
+            }
+
+            (
+                BrainKind::Neat,
+                ExportedBrainNetwork::Neat {
+                    genome,
+                    hidden_activation,
+                    output_activation,
+                },
+            ) => {
+                let inputs = Eye::from_config(config).inputs();
+
+                // (`graph()` looks nodes up by their ids and expects
+                // inputs to go first, then outputs, then hidden nodes)
+                let kind_at = |idx| match idx {
+                    idx if idx < inputs => ga::NodeKind::Input,
+                    idx if idx < inputs + Self::OUTPUTS => ga::NodeKind::Output,
+                    _ => ga::NodeKind::Hidden,
+                };
+
+                let fits = genome.nodes().windows(2).all(|nodes| nodes[0].id < nodes[1].id)
+                    && genome.nodes().len() >= inputs + Self::OUTPUTS
+                    && genome
+                        .nodes()
+                        .iter()
+                        .enumerate()
+                        .all(|(idx, node)| node.kind == kind_at(idx))
+                    && *hidden_activation == config.brain_hidden_activation
+                    && *output_activation == config.brain_output_activation;
+
+                if !fits {
+                    return Err(Error::IncompatibleBrain);
+                }
+
+                // Rejects connections to unknown nodes, into inputs and
+                // cycles - including the disabled connections, since they
+                // can get re-enabled through crossover
+                Self::try_graph(config, genome, genome.connections())?;
+
+                Ok(Self::from_genome(config, neat.adopt(genome)))
+            }
+
+            _ => Err(Error::IncompatibleBrain),
+        }
+    }
+
+    /// (NEAT brains are feed-forward only, so they don't use `memory`.)
+    crate fn propagate(&self, vision: Vec<f32>, memory: &mut nn::Memory) -> (f32, f32) {
+        let response = match &self.nn {
+            Network::Layered(nn) => nn.propagate_with_memory(vision, memory),
+            Network::Neat(neat) => neat.nn.propagate(vision),
+        };
 
         let r0 = response[0].clamp(0.0, 1.0) - 0.5;
         let r1 = response[1].clamp(0.0, 1.0) - 0.5;
@@ -34,26 +181,176 @@ impl Brain {
 
         (speed, rotation)
     }
+
+    /// Same as `propagate()`, but returns brain's hidden activations (of
+    /// all the hidden layers, one after another, or - for NEAT - of all
+    /// the hidden nodes) and its raw outputs.
+    crate fn activations(&self, vision: Vec<f32>, memory: &mut nn::Memory) -> (Vec<f32>, Vec<f32>) {
+        match &self.nn {
+            Network::Layered(nn) => {
+                let mut layers = nn.trace_with_memory(vision, memory);
+                let outputs = layers.pop().unwrap();
+
+                (layers.concat(), outputs)
+            }
+
+            Network::Neat(neat) => {
+                let mut hidden = neat.nn.trace(vision);
+                let outputs = hidden.drain(..neat.nn.outputs()).collect();
+
+                (hidden, outputs)
+            }
+        }
+    }
 }
 
 impl Brain {
-    fn new(nn: nn::Network) -> Self {
+    fn new(config: &Config, nn: Network) -> Self {
         Self {
-            speed_accel: 0.01, // config.sim_speed_accel,
-            rotation_accel: 0.1, // config.sim_rotation_accel,
+            speed_accel: config.sim_speed_accel,
+            rotation_accel: config.sim_rotation_accel,
             nn,
         }
     }
 
-    fn topology(config: &Config) -> [nn::LayerTopology; 3] {
+    fn topology(config: &Config, species: Species) -> [nn::LayerTopology; 3] {
+        let hidden_neurons = match species {
+            Species::Prey => config.brain_neurons,
+            Species::Predator => config.predator_brain_neurons,
+        };
+
         [
             nn::LayerTopology {
-                neurons: config.eye_cells,
+                neurons: Eye::from_config(config).inputs(),
+                activation: nn::Activation::Identity,
+                recurrent: false,
+            },
+            nn::LayerTopology {
+                neurons: hidden_neurons,
+                activation: config.brain_hidden_activation,
+                recurrent: config.brain_recurrent,
             },
             nn::LayerTopology {
-                neurons: config.brain_neurons,
+                neurons: Self::OUTPUTS,
+                activation: config.brain_output_activation,
+                recurrent: false,
             },
-            nn::LayerTopology { neurons: 2 },
         ]
     }
+
+    /// Builds network out of genome's enabled connections.
+    ///
+    /// Genome's nodes are sorted so that inputs go first, then outputs and
+    /// then hidden nodes - which is exactly how `GraphNetwork` indexes
+    /// them.
+    fn graph(config: &Config, genome: &ga::Genome) -> nn::GraphNetwork {
+        let connections = genome.connections().iter().filter(|connection| connection.enabled);
+
+        Self::try_graph(config, genome, connections).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Builds a network out of genome's nodes and given connections.
+    fn try_graph<'a>(
+        config: &Config,
+        genome: &ga::Genome,
+        connections: impl IntoIterator<Item = &'a ga::ConnectionGene>,
+    ) -> Result<nn::GraphNetwork, nn::Error> {
+        let index_of = |id| {
+            genome
+                .nodes()
+                .binary_search_by_key(&id, |node: &ga::NodeGene| node.id)
+                .map_err(|_| nn::Error::UnknownNode(id))
+        };
+
+        let inputs = genome
+            .nodes()
+            .iter()
+            .filter(|node| node.kind == ga::NodeKind::Input)
+            .count();
+
+        let nodes = genome.nodes()[inputs..]
+            .iter()
+            .map(|node| nn::GraphNode {
+                bias: node.bias,
+                activation: match node.kind {
+                    ga::NodeKind::Output => config.brain_output_activation,
+                    _ => config.brain_hidden_activation,
+                },
+            })
+            .collect();
+
+        let connections = connections
+            .into_iter()
+            .map(|connection| {
+                Ok(nn::GraphConnection {
+                    from: index_of(connection.from)?,
+                    to: index_of(connection.to)?,
+                    weight: connection.weight,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        nn::GraphNetwork::try_new(inputs, Self::OUTPUTS, nodes, &connections)
+    }
+}
+
+/// Layered brains are serialized the same way they've been before NEAT
+/// brains came along, so that older snapshots can still be loaded.
+#[derive(Serialize)]
+struct BrainRef<'a> {
+    speed_accel: f32,
+    rotation_accel: f32,
+    nn: Option<&'a nn::Network>,
+    neat: Option<&'a NeatNetwork>,
+}
+
+#[derive(Deserialize)]
+struct BrainRepr {
+    speed_accel: f32,
+    rotation_accel: f32,
+    #[serde(default)]
+    nn: Option<nn::Network>,
+    #[serde(default)]
+    neat: Option<NeatNetwork>,
+}
+
+impl Serialize for Brain {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let (nn, neat) = match &self.nn {
+            Network::Layered(nn) => (Some(nn), None),
+            Network::Neat(neat) => (None, Some(neat)),
+        };
+
+        BrainRef {
+            speed_accel: self.speed_accel,
+            rotation_accel: self.rotation_accel,
+            nn,
+            neat,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Brain {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let brain = BrainRepr::deserialize(deserializer)?;
+
+        let nn = match (brain.nn, brain.neat) {
+            (Some(nn), None) => Network::Layered(nn),
+            (None, Some(neat)) => Network::Neat(neat),
+            _ => return Err(de::Error::custom("expected exactly one of `nn` and `neat`")),
+        };
+
+        Ok(Self {
+            speed_accel: brain.speed_accel,
+            rotation_accel: brain.rotation_accel,
+            nn,
+        })
+    }
 }
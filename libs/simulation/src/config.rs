@@ -0,0 +1,495 @@
+use crate::*;
+use rand::seq::SliceRandom;
+use std::f32::consts::*;
+
+/// Every tunable of the simulation, gathered in one place so that
+/// experiments can vary them without recompiling.
+///
+/// When deserialized, missing fields fall back to their defaults.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Number of neurons in the brain's hidden layer
+    pub brain_neurons: usize,
+    /// Activation of the brain's hidden layer
+    pub brain_hidden_activation: nn::Activation,
+    /// Activation of the brain's output layer
+    pub brain_output_activation: nn::Activation,
+    /// Whether the brain's hidden layer remembers its previous outputs,
+    /// letting animals react to things they've seen a moment ago
+    pub brain_recurrent: bool,
+    /// How brains are represented (and, thus, how they evolve)
+    pub brain_kind: BrainKind,
+
+    /// How far an eye can see
+    pub eye_fov_range: f32,
+    /// How wide an eye can see (in radians)
+    pub eye_fov_angle: f32,
+    /// Number of photoreceptors in each eye
+    pub eye_cells: usize,
+    /// Whether eyes get a second channel, seeing other animals
+    pub eye_see_animals: bool,
+    /// Whether eyes get an extra channel, seeing how far the walls are
+    /// (requires `Boundary::Walled`, since a torus has no walls)
+    pub eye_see_walls: bool,
+
+    /// Distance at which an animal eats a food
+    pub food_size: f32,
+
+    /// Number of neurons in the predators' brains' hidden layer
+    pub predator_brain_neurons: usize,
+    /// Distance at which a predator catches a prey
+    pub predator_catch_size: f32,
+    /// `ga_mut_chance` for the predators
+    pub predator_ga_mut_chance: f32,
+    /// `ga_mut_coeff` for the predators
+    pub predator_ga_mut_coeff: f32,
+    /// `fitness_survival` for the predators (whose fitness is otherwise
+    /// the number of prey they've caught)
+    pub predator_fitness_survival: f32,
+
+    /// Energy each animal is born with; animals whose energy drops to
+    /// zero die
+    pub energy_initial: f32,
+    /// Upper bound on an animal's energy
+    pub energy_max: f32,
+    /// Energy restored by eating a food
+    pub energy_food: f32,
+    /// Energy spent on each step just by being alive
+    pub energy_cost_base: f32,
+    /// Energy spent on each step per unit of speed
+    pub energy_cost_speed: f32,
+    /// Energy spent per radian of rotation
+    pub energy_cost_rotation: f32,
+
+    /// Fitness awarded for surviving the entire generation (animals
+    /// that starve earlier get a proportional part of it)
+    pub fitness_survival: f32,
+    /// Fitness taken away from a prey that's been caught (fitness never
+    /// drops below zero, though)
+    pub fitness_caught: f32,
+
+    /// How parents get picked (`BrainKind::Layered` only - NEAT picks
+    /// them within species)
+    pub ga_selection: Selection,
+    /// How parents' brains get combined into a child's one
+    pub ga_crossover: Crossover,
+    /// How children's genes get mutated
+    pub ga_mutation: Mutation,
+    /// Probability of changing a gene during mutation
+    pub ga_mut_chance: f32,
+    /// Magnitude of a gene's change during mutation (`Mutation::Gaussian`
+    /// only)
+    pub ga_mut_coeff: f32,
+    /// Number of the fittest animals whose brains are carried over to
+    /// the next generation unchanged
+    pub ga_elite_count: usize,
+    /// (`Evolution::Continuous` only) satiation an animal has to gather
+    /// to reproduce; reproducing consumes it
+    pub ga_reproduction_satiation: usize,
+    /// (`Evolution::Continuous` only) how far an animal looks for a
+    /// mate; when there's nobody around, it reproduces asexually
+    pub ga_mate_range: f32,
+
+    pub sim_speed_min: f32,
+    pub sim_speed_max: f32,
+    pub sim_speed_accel: f32,
+    pub sim_rotation_accel: f32,
+    /// Number of steps before the population gets evolved (or, for
+    /// `Evolution::Continuous`, before statistics get reported)
+    pub sim_generation_length: usize,
+    /// How the population evolves
+    pub sim_evolution: Evolution,
+    /// Number of steps after which an animal dies of old age
+    pub sim_lifetime_max: usize,
+
+    /// What happens at the world's edges
+    pub world_boundary: Boundary,
+    /// Number of animals of the `Species::Prey`
+    pub world_animals: usize,
+    /// Number of animals of the `Species::Predator`; in
+    /// `Evolution::Continuous` predators don't get topped up, so once
+    /// they die out, they're gone
+    pub world_predators: usize,
+    /// (`Evolution::Continuous` only) when population of prey drops
+    /// below this, it gets topped up with random prey
+    pub world_animals_min: usize,
+    /// (`Evolution::Continuous` only) no animal gets born while there
+    /// are this many of them
+    pub world_animals_max: usize,
+    pub world_foods: usize,
+
+    /// (`BrainKind::Neat` only) genomes whose compatibility distance is
+    /// below this belong to the same species; weights get mutated
+    /// according to `ga_mut_chance` and `ga_mut_coeff`
+    pub neat_compatibility_threshold: f32,
+    /// (`BrainKind::Neat` only) probability of a child's genome getting a
+    /// new node
+    pub neat_add_node_chance: f32,
+    /// (`BrainKind::Neat` only) probability of a child's genome getting a
+    /// new connection
+    pub neat_add_connection_chance: f32,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            brain_neurons: 18,
+            brain_hidden_activation: nn::Activation::Relu,
+            brain_output_activation: nn::Activation::Relu,
+            brain_recurrent: false,
+            brain_kind: BrainKind::Layered,
+            eye_fov_range: 0.25,
+            eye_fov_angle: PI + FRAC_PI_4,
+            eye_cells: 9,
+            eye_see_animals: false,
+            eye_see_walls: false,
+            food_size: 0.01,
+            predator_brain_neurons: 18,
+            predator_catch_size: 0.01,
+            predator_ga_mut_chance: 0.01,
+            predator_ga_mut_coeff: 0.3,
+            predator_fitness_survival: 0.0,
+            // All the costs are zero, so - unless configured otherwise -
+            // animals never starve:
+            energy_initial: 1.0,
+            energy_max: 2.0,
+            energy_food: 0.5,
+            energy_cost_base: 0.0,
+            energy_cost_speed: 0.0,
+            energy_cost_rotation: 0.0,
+            fitness_survival: 0.0,
+            fitness_caught: 1.0,
+            ga_selection: Selection::Roulette,
+            ga_crossover: Crossover::Uniform,
+            ga_mutation: Mutation::Gaussian,
+            ga_mut_chance: 0.01,
+            ga_mut_coeff: 0.3,
+            ga_elite_count: 0,
+            ga_reproduction_satiation: 3,
+            ga_mate_range: 0.1,
+            sim_speed_min: 0.001,
+            sim_speed_max: 0.005,
+            sim_speed_accel: 0.2,
+            sim_rotation_accel: FRAC_PI_2,
+            sim_generation_length: 2500,
+            sim_evolution: Evolution::Generational,
+            sim_lifetime_max: 5000,
+            world_boundary: Boundary::Toroidal,
+            world_animals: 40,
+            world_predators: 0,
+            world_animals_min: 10,
+            world_animals_max: 80,
+            world_foods: 60,
+            neat_compatibility_threshold: 3.0,
+            neat_add_node_chance: 0.03,
+            neat_add_connection_chance: 0.05,
+        }
+    }
+}
+
+impl Config {
+    /// Returns `(ga_mut_chance, ga_mut_coeff)` of given species.
+    crate fn mutation_of(&self, species: Species) -> (f32, f32) {
+        match species {
+            Species::Prey => (self.ga_mut_chance, self.ga_mut_coeff),
+            Species::Predator => (self.predator_ga_mut_chance, self.predator_ga_mut_coeff),
+        }
+    }
+
+    /// Checks whether a simulation can be created out of this config.
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.world_animals == 0 {
+            return Err(Error::NoAnimals);
+        }
+
+        if self.sim_generation_length == 0 {
+            return Err(Error::EmptyGeneration);
+        }
+
+        if self.sim_lifetime_max == 0 {
+            return Err(Error::EmptyLifetime);
+        }
+
+        // NaNs compare false to everything, so they have to be rejected
+        // explicitly
+        let not_positive = |x: f32| x.is_nan() || x <= 0.0;
+        let negative = |x: f32| x.is_nan() || x < 0.0;
+
+        if not_positive(self.eye_fov_range)
+            || not_positive(self.eye_fov_angle)
+            || self.eye_cells == 0
+        {
+            return Err(Error::InvalidEye);
+        }
+
+        if self.eye_see_walls && self.world_boundary == Boundary::Toroidal {
+            return Err(Error::InvisibleWalls);
+        }
+
+        if !self.sim_speed_min.is_finite()
+            || !self.sim_speed_max.is_finite()
+            || self.sim_speed_min > self.sim_speed_max
+        {
+            return Err(Error::InvalidSpeed);
+        }
+
+        if negative(self.sim_speed_accel) || negative(self.sim_rotation_accel) {
+            return Err(Error::InvalidAcceleration);
+        }
+
+        if self.world_animals_min > self.world_animals_max {
+            return Err(Error::InvalidPopulationBounds);
+        }
+
+        // Negative (or NaN) survival rewards would yield fitnesses that
+        // can't be weighed by the roulette wheel nor shared among NEAT's
+        // species; sizes, energies and distances can't be negative either
+        let non_negatives = [
+            ("fitness_survival", self.fitness_survival),
+            ("predator_fitness_survival", self.predator_fitness_survival),
+            ("food_size", self.food_size),
+            ("predator_catch_size", self.predator_catch_size),
+            ("energy_initial", self.energy_initial),
+            ("energy_max", self.energy_max),
+            ("energy_food", self.energy_food),
+            ("energy_cost_base", self.energy_cost_base),
+            ("energy_cost_speed", self.energy_cost_speed),
+            ("energy_cost_rotation", self.energy_cost_rotation),
+            ("neat_compatibility_threshold", self.neat_compatibility_threshold),
+        ];
+
+        for &(name, value) in &non_negatives {
+            if negative(value) {
+                return Err(Error::InvalidParameter { name, value });
+            }
+        }
+
+        ga::GaussianMutation::try_new(self.ga_mut_chance, self.ga_mut_coeff)?;
+        ga::GaussianMutation::try_new(self.predator_ga_mut_chance, self.predator_ga_mut_coeff)?;
+        self.ga_selection.validate()?;
+        self.ga_crossover.validate()?;
+        self.ga_mutation.method(self.ga_mut_chance, self.ga_mut_coeff)?;
+        self.ga_mutation.method(self.predator_ga_mut_chance, self.predator_ga_mut_coeff)?;
+
+        for &chance in &[self.neat_add_node_chance, self.neat_add_connection_chance] {
+            if !(0.0..=1.0).contains(&chance) {
+                return Err(ga::Error::InvalidChance(chance).into());
+            }
+        }
+
+        Brain::validate(self, Species::Prey)?;
+        Brain::validate(self, Species::Predator)?;
+
+        Ok(())
+    }
+}
+
+/// How brains are represented
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum BrainKind {
+    /// Fixed, three-layer network; only its weights evolve
+    Layered,
+
+    /// NEAT genome - starts with inputs connected straight to outputs,
+    /// and then evolves both the weights and the network's shape
+    Neat,
+}
+
+/// How the population evolves
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Evolution {
+    /// Each `sim_generation_length` steps the entire population gets
+    /// replaced by a new one
+    Generational,
+
+    /// Animals that gather enough satiation reproduce during the run,
+    /// old and starved ones die - there are no generation boundaries
+    Continuous,
+}
+
+/// What happens at the world's edges
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Boundary {
+    /// Edges wrap around - an animal leaving through the right edge
+    /// reappears on the left one, and it can see (and eat) food lying
+    /// just across any edge
+    Toroidal,
+
+    /// Edges are walls that animals can't pass or see through
+    Walled,
+}
+
+impl Boundary {
+    /// Returns the shortest vector leading from `from` to `to`.
+    pub fn offset(self, from: na::Point2<f32>, to: na::Point2<f32>) -> na::Vector2<f32> {
+        let offset = to - from;
+
+        match self {
+            // Minimum image convention - out of all the copies of `to`
+            // tiling the plane, pick the closest one
+            Self::Toroidal => offset.map(|coord| coord - coord.round()),
+            Self::Walled => offset,
+        }
+    }
+
+    pub fn distance(self, from: na::Point2<f32>, to: na::Point2<f32>) -> f32 {
+        self.offset(from, to).norm()
+    }
+
+    /// Brings position that has left the world back into it.
+    pub fn confine(self, position: na::Point2<f32>) -> na::Point2<f32> {
+        match self {
+            Self::Toroidal => position.map(|coord| na::wrap(coord, 0.0, 1.0)),
+            Self::Walled => position.map(|coord| coord.clamp(0.0, 1.0)),
+        }
+    }
+}
+
+impl Default for Boundary {
+    fn default() -> Self {
+        Self::Toroidal
+    }
+}
+
+/// Selection method used by the genetic algorithm; see the
+/// corresponding types in `lib_genetic_algorithm` for details.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Selection {
+    /// Weighs animals by their fitness; when nobody has scored, there's
+    /// nothing to weigh them by, so everybody gets the same chance
+    Roulette,
+    Tournament { size: usize },
+    Rank,
+    Truncation { fraction: f32 },
+}
+
+/// Crossover method used by the genetic algorithm; see the
+/// corresponding types in `lib_genetic_algorithm` for details.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Crossover {
+    Uniform,
+    SinglePoint,
+    KPoint { k: usize },
+    Arithmetic { alpha: f32 },
+    SimulatedBinary { eta: f32 },
+}
+
+/// Mutation method used by the genetic algorithm; each of them changes
+/// a gene with probability of `Config::ga_mut_chance`. See the
+/// corresponding types in `lib_genetic_algorithm` for details.
+///
+/// (`SelfAdaptiveMutation` is not supported, since it needs chromosomes
+/// to carry step sizes along with brains' weights.)
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Mutation {
+    /// Changes genes by at most `Config::ga_mut_coeff`
+    Gaussian,
+    Normal { sigma: f32 },
+    Annealed { initial_sigma: f32, decay: f32 },
+}
+
+impl Mutation {
+    crate fn method(
+        self,
+        chance: f32,
+        coeff: f32,
+    ) -> Result<Box<dyn ga::MutationMethod>, ga::Error> {
+        Ok(match self {
+            Self::Gaussian => Box::new(ga::GaussianMutation::try_new(chance, coeff)?),
+            Self::Normal { sigma } => Box::new(ga::NormalMutation::try_new(chance, sigma)?),
+            Self::Annealed {
+                initial_sigma,
+                decay,
+            } => Box::new(ga::AnnealedMutation::try_new(chance, initial_sigma, decay)?),
+        })
+    }
+}
+
+impl Selection {
+    /// Checks whether this selection's parameters are within their
+    /// ranges.
+    crate fn validate(self) -> Result<(), ga::Error> {
+        match self {
+            Self::Roulette | Self::Rank => Ok(()),
+            Self::Tournament { size } => ga::TournamentSelection::try_new(size).map(drop),
+            Self::Truncation { fraction } => ga::TruncationSelection::try_new(fraction).map(drop),
+        }
+    }
+}
+
+impl ga::SelectionMethod for Selection {
+    fn try_select<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+    ) -> Result<&'a I, ga::Error>
+    where
+        I: ga::Individual,
+    {
+        match *self {
+            Self::Roulette => match ga::RouletteWheelSelection::new().try_select(rng, population) {
+                Err(ga::Error::ZeroFitness) => Ok(population.choose(rng).unwrap()),
+                result => result,
+            },
+            Self::Tournament { size } => ga::TournamentSelection::try_new(size)?
+                .try_select(rng, population),
+            Self::Rank => ga::RankSelection::new().try_select(rng, population),
+            Self::Truncation { fraction } => ga::TruncationSelection::try_new(fraction)?
+                .try_select(rng, population),
+        }
+    }
+
+    fn try_select_presorted<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        sorted: &[&'a I],
+    ) -> Result<&'a I, ga::Error>
+    where
+        I: ga::Individual,
+    {
+        match *self {
+            Self::Roulette | Self::Tournament { .. } => self.try_select(rng, population),
+            Self::Rank => ga::RankSelection::new().try_select_presorted(rng, population, sorted),
+            Self::Truncation { fraction } => ga::TruncationSelection::try_new(fraction)?
+                .try_select_presorted(rng, population, sorted),
+        }
+    }
+}
+
+impl Crossover {
+    /// Checks whether this crossover's parameters are within their
+    /// ranges.
+    crate fn validate(self) -> Result<(), ga::Error> {
+        match self {
+            Self::Uniform | Self::SinglePoint => Ok(()),
+            Self::KPoint { k } => ga::KPointCrossover::try_new(k).map(drop),
+            Self::Arithmetic { alpha } => ga::ArithmeticCrossover::try_new(alpha).map(drop),
+            Self::SimulatedBinary { eta } => ga::SimulatedBinaryCrossover::try_new(eta).map(drop),
+        }
+    }
+}
+
+impl ga::CrossoverMethod for Crossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &ga::Chromosome,
+        parent_b: &ga::Chromosome,
+    ) -> ga::Chromosome {
+        match *self {
+            Self::Uniform => ga::UniformCrossover::new().crossover(rng, parent_a, parent_b),
+            Self::SinglePoint => {
+                ga::SinglePointCrossover::new().crossover(rng, parent_a, parent_b)
+            }
+            Self::KPoint { k } => ga::KPointCrossover::new(k).crossover(rng, parent_a, parent_b),
+            Self::Arithmetic { alpha } => {
+                ga::ArithmeticCrossover::new(alpha).crossover(rng, parent_a, parent_b)
+            }
+            Self::SimulatedBinary { eta } => {
+                ga::SimulatedBinaryCrossover::new(eta).crossover(rng, parent_a, parent_b)
+            }
+        }
+    }
+}
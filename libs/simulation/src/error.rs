@@ -0,0 +1,107 @@
+use crate::*;
+use std::fmt;
+
+/// Reason why a simulation can't be created out of given config.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    /// Config describes something the genetic algorithm can't work with
+    /// (e.g. a mutation chance above 1.0)
+    Genetic(ga::Error),
+
+    /// Config describes brains that can't be built (e.g. without any
+    /// eye cells)
+    Neural(nn::Error),
+
+    /// Eye's range, angle or number of cells is not positive
+    InvalidEye,
+
+    /// `eye_see_walls` is set, but `world_boundary` is
+    /// `Boundary::Toroidal` - there are no walls to see
+    InvisibleWalls,
+
+    /// `sim_speed_min` or `sim_speed_max` is not finite, or the minimum
+    /// exceeds the maximum
+    InvalidSpeed,
+
+    /// `sim_speed_accel` or `sim_rotation_accel` is negative (or NaN)
+    InvalidAcceleration,
+
+    /// `world_animals_min` exceeds `world_animals_max`
+    InvalidPopulationBounds,
+
+    /// Parameter that can't be negative (e.g. a size or an energy cost)
+    /// is negative or NaN
+    InvalidParameter { name: &'static str, value: f32 },
+
+    /// `world_animals` is zero - there'd be nobody to evolve
+    NoAnimals,
+
+    /// `sim_generation_length` is zero - generations would end before
+    /// anybody got a chance to do anything
+    EmptyGeneration,
+
+    /// `sim_lifetime_max` is zero - animals would die of old age right
+    /// after being born
+    EmptyLifetime,
+
+    /// Imported brain doesn't fit animals of the simulation it's being
+    /// imported into (e.g. it's of a different kind or has a different
+    /// number of inputs)
+    IncompatibleBrain,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Genetic(err) => write!(f, "invalid genetic algorithm: {}", err),
+            Self::Neural(err) => write!(f, "invalid brain: {}", err),
+            Self::InvalidEye => write!(f, "eye's range, angle and cells must be positive"),
+            Self::InvisibleWalls => write!(f, "eyes can see walls only in a walled world"),
+            Self::InvalidSpeed => {
+                write!(f, "speed's minimum and maximum must be finite, with min <= max")
+            }
+            Self::InvalidAcceleration => write!(f, "accelerations must not be negative"),
+            Self::InvalidPopulationBounds => {
+                write!(f, "minimum number of animals must not exceed the maximum")
+            }
+            Self::InvalidParameter { name, value } => {
+                write!(f, "{} must not be negative, got {}", name, value)
+            }
+            Self::NoAnimals => write!(f, "world has no animals"),
+            Self::EmptyGeneration => write!(f, "generation must last at least one step"),
+            Self::EmptyLifetime => write!(f, "animals must live at least one step"),
+            Self::IncompatibleBrain => write!(f, "brain doesn't fit animals of this simulation"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Genetic(err) => Some(err),
+            Self::Neural(err) => Some(err),
+            Self::InvalidEye
+            | Self::InvisibleWalls
+            | Self::InvalidSpeed
+            | Self::InvalidAcceleration
+            | Self::InvalidPopulationBounds
+            | Self::InvalidParameter { .. }
+            | Self::NoAnimals
+            | Self::EmptyGeneration
+            | Self::EmptyLifetime
+            | Self::IncompatibleBrain => None,
+        }
+    }
+}
+
+impl From<ga::Error> for Error {
+    fn from(err: ga::Error) -> Self {
+        Self::Genetic(err)
+    }
+}
+
+impl From<nn::Error> for Error {
+    fn from(err: nn::Error) -> Self {
+        Self::Neural(err)
+    }
+}
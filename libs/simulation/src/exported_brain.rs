@@ -0,0 +1,43 @@
+use crate::*;
+
+/// Brain of a single animal, taken out of a simulation so that it can be
+/// stored and then put into another one.
+///
+/// See `Simulation::export_brain()` and `Simulation::try_import_brain()`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExportedBrain {
+    pub species: Species,
+
+    /// Generation the animal has been living in when it got exported
+    pub generation: usize,
+
+    /// Fitness the animal has gathered until it got exported
+    pub fitness: f32,
+
+    pub network: ExportedBrainNetwork,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ExportedBrainNetwork {
+    Layered(nn::ExportedNetwork),
+
+    /// NEAT brains get exported as their genomes (which the networks are
+    /// then re-built from), so that they can keep evolving after being
+    /// imported
+    Neat {
+        genome: ga::Genome,
+        hidden_activation: nn::Activation,
+        output_activation: nn::Activation,
+    },
+}
+
+#[cfg(feature = "json")]
+impl ExportedBrain {
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
@@ -1,43 +1,161 @@
 use crate::*;
 use std::f32::consts::*;
 
-const FOV_RANGE: f32 = 0.25;
-const FOV_ANGLE: f32 = PI + FRAC_PI_4;
-const CELLS: usize = 9;
-
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Eye {
     crate fov_range: f32,
     crate fov_angle: f32,
     crate cells: usize,
+    #[serde(default)]
+    crate boundary: Boundary,
+    #[serde(default)]
+    crate see_animals: bool,
+    #[serde(default)]
+    crate see_walls: bool,
+}
+
+/// Everything an eye can look at during a single step
+crate struct Surroundings<'a> {
+    crate foods: &'a [Food],
+    crate food_grid: &'a Grid,
+    crate animals: &'a [na::Point2<f32>],
+    crate animal_grid: &'a Grid,
+    crate prey: &'a [na::Point2<f32>],
+    crate prey_grid: &'a Grid,
 }
 
 impl Eye {
-// FOV_RANGE, FOV_ANGLE & CELLS are the values we'll use during
-    // simulation - but being able to create an arbitrary eye will
-    // come handy during the testing:
-    fn new(fov_range: f32, fov_angle: f32, cells: usize) -> Self {
+    crate fn from_config(config: &Config) -> Self {
+        Self {
+            see_animals: config.eye_see_animals,
+            see_walls: config.eye_see_walls,
+            ..Self::new(
+                config.eye_fov_range,
+                config.eye_fov_angle,
+                config.eye_cells,
+                config.world_boundary,
+            )
+        }
+    }
+
+    // The config's values are the ones we'll use during simulation -
+    // but being able to create an arbitrary eye will come handy during
+    // the testing:
+    fn new(fov_range: f32, fov_angle: f32, cells: usize, boundary: Boundary) -> Self {
         assert!(fov_range > 0.0);
         assert!(fov_angle > 0.0);
         assert!(cells > 0);
 
-        Self { fov_range, fov_angle, cells }
+        Self {
+            fov_range,
+            fov_angle,
+            cells,
+            boundary,
+            see_animals: false,
+            see_walls: false,
+        }
     }
 
     pub fn cells(&self) -> usize {
         self.cells
     }
 
+    /// Number of things this eye is sensitive to (food, animals, walls);
+    /// each channel has its own set of `cells()` photoreceptors.
+    pub fn channels(&self) -> usize {
+        1 + self.see_animals as usize + self.see_walls as usize
+    }
+
+    /// Length of vector returned from `process_senses()`.
+    pub fn inputs(&self) -> usize {
+        self.cells * self.channels()
+    }
+
+    /// Looks at the surroundings through all of the eye's channels, one
+    /// after another: food (which, for predators, means prey), then
+    /// other animals (i.e. all but the one at index `me`), then walls.
+    crate fn process_senses(
+        &self,
+        me: usize,
+        species: Species,
+        rotation: na::Rotation2<f32>,
+        surroundings: &Surroundings,
+    ) -> Vec<f32> {
+        let position = surroundings.animals[me];
+
+        let mut vision = match species {
+            Species::Prey => self.process_vision_near(
+                position,
+                rotation,
+                surroundings.foods,
+                surroundings.food_grid,
+            ),
+
+            Species::Predator => {
+                let prey = surroundings
+                    .prey_grid
+                    .query(position, self.fov_range)
+                    .map(|idx| surroundings.prey[idx]);
+
+                self.see(position, rotation, prey)
+            }
+        };
+
+        if self.see_animals {
+            let others = surroundings
+                .animal_grid
+                .query(position, self.fov_range)
+                .filter(|&idx| idx != me)
+                .map(|idx| surroundings.animals[idx]);
+
+            vision.extend(self.see(position, rotation, others));
+        }
+
+        if self.see_walls {
+            vision.extend(self.see_walls(position, rotation));
+        }
+
+        vision
+    }
+
     pub fn process_vision(
         &self,
         position: na::Point2<f32>,
         rotation: na::Rotation2<f32>,
         foods: &[Food],
+    ) -> Vec<f32> {
+        self.see(position, rotation, foods.iter().map(|food| food.position))
+    }
+
+    /// Same as `process_vision()`, but looks only at the foods that
+    /// `grid` (built over `foods`) deems close enough.
+    ///
+    /// Foods get visited in a different order, so the result might
+    /// differ from `process_vision()`'s by a rounding error.
+    pub fn process_vision_near(
+        &self,
+        position: na::Point2<f32>,
+        rotation: na::Rotation2<f32>,
+        foods: &[Food],
+        grid: &Grid,
+    ) -> Vec<f32> {
+        let nearby = grid
+            .query(position, self.fov_range)
+            .map(|idx| foods[idx].position);
+
+        self.see(position, rotation, nearby)
+    }
+
+    fn see(
+        &self,
+        position: na::Point2<f32>,
+        rotation: na::Rotation2<f32>,
+        targets: impl IntoIterator<Item = na::Point2<f32>>,
     ) -> Vec<f32> {
         let mut cells = vec![0.0; self.cells];
 
-        for food in foods {
-            let dist_vev = food.position - position;
+        for target in targets {
+            let dist_vev = self.boundary.offset(position, target);
             let dist = dist_vev.norm();
             if dist >= self.fov_range {
                 continue
@@ -62,11 +180,42 @@ impl Eye {
 
         cells
     }
+
+    /// Casts a ray through the middle of each cell; the closer the wall
+    /// it hits, the brighter the cell.
+    fn see_walls(&self, position: na::Point2<f32>, rotation: na::Rotation2<f32>) -> Vec<f32> {
+        if self.boundary == Boundary::Toroidal {
+            return vec![0.0; self.cells];
+        }
+
+        (0..self.cells)
+            .map(|cell| {
+                let angle = -self.fov_angle / 2.0
+                    + (cell as f32 + 0.5) * self.fov_angle / (self.cells as f32);
+
+                let dir = na::Rotation2::new(rotation.angle() + angle) * na::Vector2::x();
+
+                let dist_to_wall = |pos: f32, dir: f32| {
+                    if dir > 0.0 {
+                        (1.0 - pos) / dir
+                    } else if dir < 0.0 {
+                        -pos / dir
+                    } else {
+                        f32::INFINITY
+                    }
+                };
+
+                let dist = dist_to_wall(position.x, dir.x).min(dist_to_wall(position.y, dir.y));
+
+                ((self.fov_range - dist) / self.fov_range).max(0.0)
+            })
+            .collect()
+    }
 }
 
 impl Default for Eye {
     fn default() -> Self {
-        Self::new(FOV_RANGE, FOV_ANGLE, CELLS)
+        Self::from_config(&Config::default())
     }
 }
 
@@ -81,6 +230,7 @@ mod tests {
         x: f32,
         y: f32,
         rot: f32,
+        boundary: Boundary,
         expected_vision: &'static str,
     }
 
@@ -91,6 +241,7 @@ mod tests {
                 self.fov_range,
                 self.fov_angle,
                 TEST_EYE_CELLS,
+                self.boundary,
             );
 
             let actual_vision = eye.process_vision(
@@ -102,7 +253,12 @@ mod tests {
                 assert!(actual_vision[i] <= 1.0);
                 assert!(actual_vision[i] >= 0.0);
             }
-            let actual_vision: Vec<_> = actual_vision
+            assert_eq!(render(actual_vision), String::from(self.expected_vision));
+        }
+    }
+
+    fn render(vision: Vec<f32>) -> String {
+        let vision: Vec<_> = vision
             .into_iter()
             .map(|cell| {
                 // As a reminder, the higher cell's value, the closer the food is:
@@ -117,9 +273,7 @@ mod tests {
                 }
             })
             .collect();
-            let actual_vision = actual_vision.join("");
-            assert_eq!(actual_vision, String::from(self.expected_vision));
-        }
+        vision.join("")
     }
 
     fn food(x: f32, y: f32) -> Food {
@@ -150,6 +304,7 @@ mod tests {
                 x: 0.5,
                 y: 0.5,
                 rot: 0.0,
+                boundary: Boundary::Walled,
                 expected_vision,
             }.run()
         }
@@ -177,6 +332,7 @@ mod tests {
                 x: 0.5,
                 y: 0.5,
                 rot,
+                boundary: Boundary::Walled,
                 expected_vision,
             }.run()
         }
@@ -218,6 +374,7 @@ mod tests {
                 rot: 0.0,
                 x,
                 y,
+                boundary: Boundary::Walled,
                 expected_vision,
             }.run()
         }
@@ -251,8 +408,200 @@ mod tests {
                 y: 0.5,
                 rot: 0.0,
                 fov_angle,
+                boundary: Boundary::Walled,
+                expected_vision,
+            }.run()
+        }
+    }
+
+    mod across_borders {
+        use super::*;
+        use test_case::test_case;
+        // The bird's sitting next to the right edge, looking right, with
+        // the foods lying just across the edge:
+        #[test_case(0.95, Boundary::Toroidal, "  +       +  ")]
+        #[test_case(0.90, Boundary::Toroidal, "   .     .   ")]
+        #[test_case(0.85, Boundary::Toroidal, "             ")]
+        #[test_case(0.95, Boundary::Walled, "             ")]
+        fn horizontally(x: f32, boundary: Boundary, expected_vision: &'static str) {
+            TestCase {
+                foods: vec![food(0.05, 0.45), food(0.05, 0.55)],
+                fov_range: 0.2,
+                fov_angle: FRAC_PI_2,
+                rot: 0.0,
+                x,
+                y: 0.5,
+                boundary,
+                expected_vision,
+            }.run()
+        }
+
+        // The bird's sitting next to the bottom edge, looking down, with
+        // the food lying just across the edge:
+        #[test_case(0.01, Boundary::Toroidal, "      #      ")]
+        #[test_case(0.10, Boundary::Toroidal, "      +      ")]
+        #[test_case(0.15, Boundary::Toroidal, "      .      ")]
+        #[test_case(0.01, Boundary::Walled, "             ")]
+        fn vertically(y: f32, boundary: Boundary, expected_vision: &'static str) {
+            TestCase {
+                foods: vec![food(0.5, 0.98)],
+                fov_range: 0.2,
+                fov_angle: FRAC_PI_2,
+                rot: -FRAC_PI_2,
+                x: 0.5,
+                y,
+                boundary,
                 expected_vision,
             }.run()
         }
+
+        // In a corner, food across both edges is visible diagonally:
+        #[test]
+        fn diagonally() {
+            TestCase {
+                foods: vec![food(0.02, 0.02)],
+                fov_range: 0.2,
+                fov_angle: FRAC_PI_2,
+                rot: FRAC_PI_4,
+                x: 0.95,
+                y: 0.95,
+                boundary: Boundary::Toroidal,
+                expected_vision: "      +      ",
+            }.run()
+        }
+    }
+
+    mod process_senses {
+        use super::*;
+
+        fn eye(see_animals: bool, see_walls: bool) -> Eye {
+            Eye {
+                see_animals,
+                see_walls,
+                ..Eye::new(0.2, FRAC_PI_2, TEST_EYE_CELLS, Boundary::Walled)
+            }
+        }
+
+        /// Renders each channel separately, as `food|animals|walls`
+        fn process(eye: &Eye, foods: Vec<Food>, animals: Vec<na::Point2<f32>>) -> String {
+            process_as(Species::Prey, eye, foods, animals)
+        }
+
+        /// Same as `process()`, but all animals except the first one are
+        /// considered prey
+        fn process_as(
+            species: Species,
+            eye: &Eye,
+            foods: Vec<Food>,
+            animals: Vec<na::Point2<f32>>,
+        ) -> String {
+            let prey = &animals[1..];
+
+            let surroundings = Surroundings {
+                foods: &foods,
+                food_grid: &Grid::from_foods(&foods),
+                animals: &animals,
+                animal_grid: &Grid::from_positions(&animals),
+                prey,
+                prey_grid: &Grid::from_positions(prey),
+            };
+
+            let vision =
+                eye.process_senses(0, species, na::Rotation2::new(0.0), &surroundings);
+
+            assert_eq!(vision.len(), eye.inputs());
+
+            vision
+                .chunks(TEST_EYE_CELLS)
+                .map(|channel| render(channel.to_vec()))
+                .collect::<Vec<_>>()
+                .join("|")
+        }
+
+        #[test]
+        fn food_only() {
+            let actual = process(
+                &eye(false, false),
+                vec![food(0.6, 0.5)],
+                vec![na::Point2::new(0.5, 0.5), na::Point2::new(0.55, 0.5)],
+            );
+
+            assert_eq!(actual, "      +      ");
+        }
+
+        #[test]
+        fn animals() {
+            let actual = process(
+                &eye(true, false),
+                vec![food(0.6, 0.5)],
+                vec![na::Point2::new(0.5, 0.5), na::Point2::new(0.55, 0.5)],
+            );
+
+            // The animal doesn't see itself, only the other one
+            assert_eq!(actual, "      +      |      #      ");
+        }
+
+        #[test]
+        fn predators_see_prey_instead_of_food() {
+            let actual = process_as(
+                Species::Predator,
+                &eye(true, false),
+                vec![food(0.6, 0.5)],
+                vec![na::Point2::new(0.5, 0.5), na::Point2::new(0.55, 0.5)],
+            );
+
+            assert_eq!(actual, "      #      |      #      ");
+        }
+
+        #[test]
+        fn walls() {
+            let actual = process(
+                &eye(true, true),
+                vec![],
+                vec![na::Point2::new(0.9, 0.5)],
+            );
+
+            assert_eq!(actual, "             |             |+++++++++++++");
+        }
+
+        #[test]
+        fn no_walls_on_torus() {
+            let eye = Eye {
+                boundary: Boundary::Toroidal,
+                ..eye(false, true)
+            };
+
+            let actual = process(&eye, vec![], vec![na::Point2::new(0.9, 0.5)]);
+
+            assert_eq!(actual, "             |             ");
+        }
+    }
+
+    mod process_vision_near {
+        use super::*;
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha8Rng;
+
+        #[test]
+        fn matches_brute_force() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let eye = Eye::default();
+            let foods: Vec<_> = (0..500).map(|_| Food::random(&mut rng)).collect();
+            let grid = Grid::from_foods(&foods);
+
+            for _ in 0..50 {
+                let position = rng.gen();
+                let rotation = na::Rotation2::new(rng.gen_range(-PI..PI));
+
+                let expected = eye.process_vision(position, rotation, &foods);
+                let actual = eye.process_vision_near(position, rotation, &foods, &grid);
+
+                approx::assert_relative_eq!(
+                    actual.as_slice(),
+                    expected.as_slice(),
+                    epsilon = 1e-5
+                );
+            }
+        }
     }
 }
@@ -0,0 +1,174 @@
+use crate::*;
+
+/// Uniform grid over the (toroidal) world, bucketing points by the cell
+/// they fall into, so that "what's near this position?" doesn't have to
+/// look at every single point.
+///
+/// Indices stored in the grid refer to the slice it's been built from
+/// (e.g. `World::foods`).
+#[derive(Clone, Debug)]
+pub struct Grid {
+    side: usize,
+    cells: Vec<Vec<usize>>,
+}
+
+impl Grid {
+    /// Builds a grid whose cells are (roughly) `cell_size` wide.
+    pub fn new(
+        positions: impl IntoIterator<Item = na::Point2<f32>>,
+        cell_size: f32,
+    ) -> Self {
+        assert!(cell_size > 0.0);
+
+        let side = ((1.0 / cell_size) as usize).max(1);
+
+        let mut this = Self {
+            side,
+            cells: vec![Vec::new(); side * side],
+        };
+
+        for (idx, position) in positions.into_iter().enumerate() {
+            let cell = this.cell_of(position);
+            this.cells[cell].push(idx);
+        }
+
+        this
+    }
+
+    /// Builds a grid over given foods, with cells sized so that each one
+    /// holds a couple of them on average.
+    pub fn from_foods(foods: &[Food]) -> Self {
+        Self::new(
+            foods.iter().map(|food| food.position),
+            Self::cell_size_for(foods.len()),
+        )
+    }
+
+    /// Same as `from_foods()`, but for arbitrary points (e.g. animals).
+    pub fn from_positions(positions: &[na::Point2<f32>]) -> Self {
+        Self::new(positions.iter().copied(), Self::cell_size_for(positions.len()))
+    }
+
+    /// Informs the grid that point at `idx` has moved from `from` to `to`.
+    pub fn relocate(&mut self, idx: usize, from: na::Point2<f32>, to: na::Point2<f32>) {
+        let (from, to) = (self.cell_of(from), self.cell_of(to));
+
+        if from == to {
+            return;
+        }
+
+        if let Some(pos) = self.cells[from].iter().position(|&other| other == idx) {
+            self.cells[from].swap_remove(pos);
+            self.cells[to].push(idx);
+        }
+    }
+
+    /// Returns indices of points that might be within `radius` from
+    /// `position` (the caller still has to check the actual distance),
+    /// in no particular order.
+    ///
+    /// Query always wraps around the world's edges - for a walled world
+    /// that merely yields a few extra candidates.
+    pub fn query(
+        &self,
+        position: na::Point2<f32>,
+        radius: f32,
+    ) -> impl Iterator<Item = usize> + '_ {
+        let side = self.side as isize;
+        let (x, y) = self.coords_of(position);
+        let rings = (radius * self.side as f32).ceil() as isize;
+
+        // When the query covers the entire axis, visiting each cell once
+        // is enough - otherwise wrapping would yield duplicates
+        let span = move |center: usize| {
+            let (from, len) = if 2 * rings + 1 >= side {
+                (0, side)
+            } else {
+                (center as isize - rings, 2 * rings + 1)
+            };
+
+            (from..from + len).map(move |coord| coord.rem_euclid(side) as usize)
+        };
+
+        span(y)
+            .flat_map(move |y| span(x).map(move |x| y * self.side + x))
+            .flat_map(move |cell| self.cells[cell].iter().copied())
+    }
+
+    fn cell_size_for(points: usize) -> f32 {
+        const POINTS_PER_CELL: f32 = 2.0;
+
+        (POINTS_PER_CELL / points.max(1) as f32).sqrt().min(1.0)
+    }
+
+    fn coords_of(&self, position: na::Point2<f32>) -> (usize, usize) {
+        let coord = |value: f32| {
+            let value = na::wrap(value, 0.0, 1.0);
+            ((value * self.side as f32) as usize).min(self.side - 1)
+        };
+
+        (coord(position.x), coord(position.y))
+    }
+
+    fn cell_of(&self, position: na::Point2<f32>) -> usize {
+        let (x, y) = self.coords_of(position);
+        y * self.side + x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid {
+        let positions = vec![
+            na::Point2::new(0.05, 0.05),
+            na::Point2::new(0.55, 0.55),
+            na::Point2::new(0.95, 0.05),
+            na::Point2::new(0.05, 0.95),
+            na::Point2::new(0.35, 0.55),
+        ];
+
+        Grid::new(positions, 0.1)
+    }
+
+    fn query(grid: &Grid, x: f32, y: f32, radius: f32) -> Vec<usize> {
+        let mut found: Vec<_> = grid.query(na::Point2::new(x, y), radius).collect();
+        found.sort_unstable();
+        found
+    }
+
+    mod query {
+        use super::*;
+
+        #[test]
+        fn finds_nearby_points() {
+            assert_eq!(query(&grid(), 0.5, 0.5, 0.1), vec![1]);
+            assert_eq!(query(&grid(), 0.45, 0.55, 0.2), vec![1, 4]);
+        }
+
+        #[test]
+        fn wraps_around_edges() {
+            assert_eq!(query(&grid(), 0.01, 0.01, 0.1), vec![0, 2, 3]);
+        }
+
+        #[test]
+        fn covers_everything_with_large_radius() {
+            assert_eq!(query(&grid(), 0.5, 0.5, 1.0), vec![0, 1, 2, 3, 4]);
+        }
+    }
+
+    mod relocate {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut grid = grid();
+
+            grid.relocate(1, na::Point2::new(0.55, 0.55), na::Point2::new(0.25, 0.75));
+
+            assert!(query(&grid, 0.55, 0.55, 0.05).is_empty());
+            assert_eq!(query(&grid, 0.25, 0.75, 0.05), vec![1]);
+        }
+    }
+}
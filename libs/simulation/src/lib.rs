@@ -1,147 +1,1896 @@
 #![feature(crate_visibility_modifier)]
-pub use self::{animal::*, brain::*, eye::*, food::*, world::*};
+pub use self::{
+    animal::*, brain::*, config::*, error::*, exported_brain::*, eye::*, food::*, grid::*,
+    species::*, statistics::*, world::*,
+};
 
 mod animal;
 mod animal_individual;
 mod brain;
+mod config;
+mod error;
+mod exported_brain;
 mod eye;
 mod food;
+mod grid;
+mod snapshot;
+mod species;
+mod statistics;
 mod world;
 
 use self::animal_individual::*;
 use lib_genetic_algorithm as ga;
 use lib_neural_network as nn;
 use nalgebra as na;
-use rand::{Rng, RngCore};
-use std::f32::consts::FRAC_PI_2;
-
-const SPEED_MIN: f32 = 0.001;
-const SPEED_MAX: f32 = 0.005;
-const SPEED_ACCEL: f32 = 0.2;
-const ROTATION_ACCEL: f32 = FRAC_PI_2;
-const GENERATION_LENGTH: usize = 2500;
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
 
 pub struct Simulation{
+    config: Config,
     world: World,
-    ga: ga::GeneticAlgorithm<ga::RouletteWheelSelection>,
+    ga: ga::GeneticAlgorithm<Selection>,
+    predator_ga: ga::GeneticAlgorithm<Selection>,
+    /// (`BrainKind::Neat` only) unlike the genetic algorithm, NEAT
+    /// remembers its innovations, so it's not re-created on each step
+    neat: ga::Neat,
+    predator_neat: ga::Neat,
+    generation: usize,
     age: usize,
+
+    /// Simulation owns its generator, so that a snapshot can carry the
+    /// generator's state and be resumed exactly where it left off
+    rng: ChaCha8Rng,
 }
 
 impl Simulation {
-    pub fn random(rng: &mut dyn RngCore) -> Self {
-        let world = World::random(rng);
-        let ga = ga::GeneticAlgorithm::new(
-            ga::RouletteWheelSelection::new(),
-            ga::UniformCrossover::new(),
-            ga::GaussianMutation::new(0.01, 0.3),
-        );
+    /// Panics when given config is invalid - see `try_random()`.
+    pub fn random(config: Config, rng: &mut dyn RngCore) -> Self {
+        let world = World::random(&config, rng);
+        let rng = ChaCha8Rng::from_rng(rng).expect("failed to seed the simulation");
+
+        Self::new(config, world, 0, 0, rng)
+    }
+
+    /// Creates a simulation that's entirely determined by given seed -
+    /// two simulations created from the same seed and config will go
+    /// through exactly the same states, on every platform.
+    pub fn from_seed(seed: u64, config: Config) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+        Self::random(config, &mut rng)
+    }
+
+    /// Same as `random()`, but - instead of panicking - returns an error
+    /// when given config is invalid.
+    pub fn try_random(config: Config, rng: &mut dyn RngCore) -> Result<Self, Error> {
+        config.validate()?;
+
+        Ok(Self::random(config, rng))
+    }
+
+    /// Same as `from_seed()`, but - instead of panicking - returns an
+    /// error when given config is invalid.
+    pub fn try_from_seed(seed: u64, config: Config) -> Result<Self, Error> {
+        config.validate()?;
+
+        Ok(Self::from_seed(seed, config))
+    }
+
+    fn new(
+        config: Config,
+        world: World,
+        generation: usize,
+        age: usize,
+        rng: ChaCha8Rng,
+    ) -> Self {
+        // Each species gets its own mutation parameters
+        let ga = |species| {
+            let (chance, coeff) = config.mutation_of(species);
+
+            ga::GeneticAlgorithm::new(
+                config.ga_selection,
+                config.ga_crossover,
+                config
+                    .ga_mutation
+                    .method(chance, coeff)
+                    .unwrap_or_else(|err| panic!("{}", err)),
+            )
+            .with_elitism(config.ga_elite_count)
+        };
 
-        Self { world, ga, age: 0 }
+        let predator_ga = ga(Species::Predator);
+        let ga = ga(Species::Prey);
+
+        let neat = |species| {
+            let (chance, coeff) = config.mutation_of(species);
+
+            ga::Neat::new(Eye::from_config(&config).inputs(), Brain::OUTPUTS)
+                .with_compatibility(ga::Compatibility {
+                    threshold: config.neat_compatibility_threshold,
+                    ..Default::default()
+                })
+                .with_weight_mutation(chance, coeff)
+                .with_structural_mutation(
+                    config.neat_add_node_chance,
+                    config.neat_add_connection_chance,
+                )
+        };
+
+        let predator_neat = neat(Species::Predator);
+        let neat = neat(Species::Prey);
+
+        Self {
+            config,
+            world,
+            ga,
+            predator_ga,
+            neat,
+            predator_neat,
+            generation,
+            age,
+            rng,
+        }
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
     }
 
     pub fn world(&self) -> &World {
         &self.world
     }
 
+    /// Index of the generation that's currently being simulated.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Returns what the animal at given index currently sees and how its
+    /// brain reacts to that, or `None` if there's no such animal.
+    ///
+    /// Doesn't affect the simulation - animal's memory (if its brain is
+    /// recurrent) stays as it was.
+    pub fn animal_details(&self, idx: usize) -> Option<AnimalDetails> {
+        let animal = self.world.animals.get(idx)?;
+        let (animals, prey) = self.positions();
+
+        let surroundings = Surroundings {
+            foods: &self.world.foods,
+            food_grid: &Grid::from_foods(&self.world.foods),
+            animals: &animals,
+            animal_grid: &Grid::from_positions(&animals),
+            prey: &prey,
+            prey_grid: &Grid::from_positions(&prey),
+        };
+
+        let vision = animal.eye.process_senses(idx, animal.species, animal.rotation, &surroundings);
+
+        let (hidden, outputs) = animal
+            .brain
+            .activations(vision.clone(), &mut animal.memory.clone());
+
+        Some(AnimalDetails {
+            vision,
+            hidden,
+            outputs,
+            speed: animal.speed,
+            satiation: animal.satiation,
+        })
+    }
+
+    /// Exports brain of the animal at given index, or returns `None` if
+    /// there's no such animal.
+    pub fn export_brain(&self, idx: usize) -> Option<ExportedBrain> {
+        let animal = self.world.animals.get(idx)?;
+
+        Some(ExportedBrain {
+            species: animal.species,
+            generation: self.generation,
+            fitness: fitness(&self.config, animal),
+            network: animal.brain.export(&self.config),
+        })
+    }
+
+    /// Exports brain of the currently fittest animal of given species,
+    /// or returns `None` if there are no animals of that species.
+    pub fn export_fittest_brain(&self, species: Species) -> Option<ExportedBrain> {
+        let (idx, _) = self
+            .fitnesses(species)
+            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())?;
+
+        self.export_brain(idx)
+    }
+
+    /// Puts given brain into the world - in place of the least fit animal
+    /// of brain's species or, if there are none, into a new animal -
+    /// returning index of the animal that's got it.
+    ///
+    /// Fails when the brain doesn't fit animals of this simulation (e.g.
+    /// it's been exported from a simulation whose eyes have a different
+    /// number of cells).
+    pub fn try_import_brain(&mut self, brain: &ExportedBrain) -> Result<usize, Error> {
+        let species = brain.species;
+
+        let species_neat = match species {
+            Species::Prey => &mut self.neat,
+            Species::Predator => &mut self.predator_neat,
+        };
+
+        let brain = Brain::try_import(&self.config, species, &brain.network, species_neat)?;
+        let animal = Animal::from_brain(&self.config, species, brain, &mut self.rng);
+
+        let least_fit = self
+            .fitnesses(species)
+            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap());
+
+        match least_fit {
+            Some((idx, _)) => {
+                self.world.animals[idx] = animal;
+                Ok(idx)
+            }
+
+            None => {
+                self.world.animals.push(animal);
+                Ok(self.world.animals.len() - 1)
+            }
+        }
+    }
+
     /// Performs a single step - a single second, so to say - of our
     /// simulation.
-    pub fn step(&mut self, rng: &mut dyn RngCore) {
-        self.process_collisions(rng);
-        self.process_brains();
+    ///
+    /// Returns statistics of the generation that has just ended, if
+    /// this step happened to trigger the evolution.
+    pub fn step(&mut self) -> Option<GenerationStatistics> {
+        let mut grid = Grid::from_foods(&self.world.foods);
+
+        self.process_collisions(&mut grid);
+        self.process_hunting();
+        self.process_brains(&grid);
         self.process_movements();
+        self.process_deaths();
 
         self.age += 1;
 
-        if self.age > GENERATION_LENGTH {
-            // start new generation
-            self.evolve(rng);
+        match self.config.sim_evolution {
+            Evolution::Generational => {
+                if self.age > self.config.sim_generation_length
+                    || self.world.animals.is_empty()
+                {
+                    // start new generation
+                    Some(self.evolve())
+                } else {
+                    None
+                }
+            }
+
+            Evolution::Continuous => {
+                self.process_reproduction();
+
+                if self.age > self.config.sim_generation_length {
+                    self.end_period()
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// Steps through the rest of the current generation, returning its
+    /// statistics - `None` only when, in `Evolution::Continuous`, there's
+    /// no prey left to report on.
+    pub fn train(&mut self) -> Option<GenerationStatistics> {
+        let generation = self.generation;
+
+        loop {
+            let stats = self.step();
+
+            if self.generation != generation {
+                return stats;
+            }
         }
     }
 
-    fn evolve(&mut self, rng: &mut dyn RngCore) {
+    /// Same as `train()`, but goes through `n` generations, returning
+    /// statistics of each one of them.
+    pub fn train_generations(&mut self, n: usize) -> Vec<GenerationStatistics> {
+        (0..n).filter_map(|_| self.train()).collect()
+    }
+
+    fn evolve(&mut self) -> GenerationStatistics {
         self.age = 0;
 
-        // Transforms `Vec<Animal>` to `Vec<AnimalIndividual>`
-        let current_population: Vec<_> = self
+        // Animals that have starved (or have been caught) get evaluated,
+        // too
+        let (prey, predators): (Vec<_>, Vec<_>) = self
             .world
             .animals
+            .drain(..)
+            .chain(self.world.dead_animals.drain(..))
+            .partition(|animal| animal.species == Species::Prey);
+
+        // Each species evolves on its own
+        let prey_stats = self.evolve_species(Species::Prey, prey);
+        let predator_stats = self.evolve_species(Species::Predator, predators);
+
+        // Step 4: Restart foods
+        //
+        // (this is not strictly necessary, but it allows to easily spot
+        // when the evolution happens - so it's more of a UI thing.)
+        for food in &mut self.world.foods {
+            food.position = self.rng.gen();
+        }
+
+        let stats = self
+            .statistics(prey_stats, predator_stats)
+            .expect("there are no prey to evaluate");
+
+        self.generation += 1;
+        self.world.food_eaten = 0;
+        self.world.prey_caught = 0;
+
+        stats
+    }
+
+    /// Replaces given animals of a species with their offspring (which
+    /// get pushed into the world), returning how well the animals have
+    /// performed.
+    fn evolve_species(&mut self, species: Species, animals: Vec<Animal>) -> Option<ga::Statistics> {
+        if animals.is_empty() {
+            return None;
+        }
+
+        if self.config.brain_kind == BrainKind::Neat {
+            return Some(self.evolve_genomes(species, animals));
+        }
+
+        // Transforms `Vec<Animal>` to `Vec<AnimalIndividual>`
+        let current_population: Vec<_> = animals
             .iter()
-            .map(AnimalIndividual::from_animal)
+            .map(|animal| AnimalIndividual::from_animal(&self.config, animal))
             .collect();
 
+        let species_ga = match species {
+            Species::Prey => &self.ga,
+            Species::Predator => &self.predator_ga,
+        };
+
         // Evolves this `Vec<AnimalIndividual>`
-        let evolved_population = self.ga.evolve(
-            rng,
+        let evolved = species_ga.try_evolve_generation(
+            &mut self.rng,
             &current_population,
+            self.generation,
         );
 
+        let (evolved_population, stats) = match evolved {
+            Ok(evolved) => evolved,
+
+            // Selection can only fail on fitnesses it can't weigh - such
+            // population has nothing sensible to pass on, so it starts
+            // anew
+            Err(_) => {
+                let stats = ga::Statistics::new(&current_population);
+
+                for _ in 0..current_population.len() {
+                    let animal = Animal::random_species(&self.config, species, &mut self.rng);
+                    self.world.animals.push(animal);
+                }
+
+                return Some(stats);
+            }
+        };
+
         // Transforms `Vec<AnimalIndividual>` back into `Vec<Animal>`
-        self.world.animals = evolved_population
-            .into_iter()
-            .map(|individual| individual.into_animal(rng))
+        let config = &self.config;
+        let rng = &mut self.rng;
+
+        self.world.animals.extend(
+            evolved_population
+                .into_iter()
+                .map(|individual| individual.into_animal(config, species, rng)),
+        );
+
+        Some(stats)
+    }
+
+    /// `BrainKind::Neat` counterpart of `evolve_species()`.
+    ///
+    /// (populations where nobody has scored don't need special treatment
+    /// here, since NEAT doesn't select parents with a roulette wheel.)
+    fn evolve_genomes(&mut self, species: Species, animals: Vec<Animal>) -> ga::Statistics {
+        let current_population: Vec<_> = animals
+            .iter()
+            .map(|animal| AnimalGenome::from_animal(&self.config, animal))
             .collect();
 
+        let species_neat = match species {
+            Species::Prey => &mut self.neat,
+            Species::Predator => &mut self.predator_neat,
+        };
 
-        // Step 4: Restart foods
-        //
-        // (this is not strictly necessary, but it allows to easily spot
-        // when the evolution happens - so it's more of a UI thing.)
-        for food in &mut self.world.foods {
-            food.position = rng.gen();
-        }
+        let (evolved_population, stats) = species_neat.evolve(&mut self.rng, &current_population);
+
+        let config = &self.config;
+        let rng = &mut self.rng;
+
+        self.world.animals.extend(
+            evolved_population
+                .into_iter()
+                .map(|individual| individual.into_animal(config, species, rng)),
+        );
+
+        stats
     }
 
-    fn process_collisions(&mut self, rng: &mut dyn RngCore) {
-        for animal in &mut self.world.animals {
-            for food in &mut self.world.foods {
-                let distance = na::distance(
-                    &animal.position,
-                    &food.position,
-                );
+    fn statistics(
+        &self,
+        prey_stats: Option<ga::Statistics>,
+        predator_stats: Option<ga::Statistics>,
+    ) -> Option<GenerationStatistics> {
+        let stats = GenerationStatistics::new(
+            self.generation,
+            self.world.food_eaten,
+            &prey_stats?,
+        );
+
+        Some(match predator_stats {
+            Some(predator_stats) => stats.with_predators(self.world.prey_caught, &predator_stats),
+            None => stats,
+        })
+    }
+
+    /// Continuous counterpart of `evolve()` - since there are no
+    /// generations to replace, it just reports how the living animals
+    /// are doing and starts a new reporting period.
+    fn end_period(&mut self) -> Option<GenerationStatistics> {
+        self.age = 0;
+
+        let stats_of = |species| {
+            let population: Vec<_> = self
+                .world
+                .animals
+                .iter()
+                .filter(|animal| animal.species == species)
+                .map(|animal| AnimalIndividual::from_animal(&self.config, animal))
+                .collect();
+
+            if population.is_empty() {
+                None
+            } else {
+                Some(ga::Statistics::new(&population))
+            }
+        };
+
+        let stats = self.statistics(stats_of(Species::Prey), stats_of(Species::Predator));
+
+        self.generation += 1;
+        self.world.food_eaten = 0;
+        self.world.prey_caught = 0;
+
+        stats
+    }
 
-                if distance <= 0.01 {
+    fn process_collisions(&mut self, grid: &mut Grid) {
+        let prey = self
+            .world
+            .animals
+            .iter_mut()
+            .filter(|animal| animal.species == Species::Prey);
+
+        for animal in prey {
+            let nearby: Vec<_> = grid.query(animal.position, self.config.food_size).collect();
+
+            for idx in nearby {
+                let food = &mut self.world.foods[idx];
+
+                let distance = self
+                    .config
+                    .world_boundary
+                    .distance(animal.position, food.position);
+
+                if distance <= self.config.food_size {
                     animal.satiation += 1;
-                    food.position = rng.gen();
+                    self.world.food_eaten += 1;
+                    animal.energy = (animal.energy + self.config.energy_food)
+                        .min(self.config.energy_max);
+
+                    let position = self.rng.gen();
+                    grid.relocate(idx, food.position, position);
+                    food.position = position;
                 }
             }
         }
     }
 
-    fn process_brains(&mut self) {
-        for animal in &mut self.world.animals {
-            let vision = animal.eye.process_vision(
-                animal.position,
-                animal.rotation,
-                &self.world.foods,
-            );
+    fn process_brains(&mut self, food_grid: &Grid) {
+        let (animals, prey) = self.positions();
 
-            let response = animal.brain.nn.propagate(vision);
+        let surroundings = Surroundings {
+            foods: &self.world.foods,
+            food_grid,
+            animals: &animals,
+            animal_grid: &Grid::from_positions(&animals),
+            prey: &prey,
+            prey_grid: &Grid::from_positions(&prey),
+        };
 
-            let speed = response[0].clamp(
-                -SPEED_ACCEL,
-                SPEED_ACCEL,
+        for (idx, animal) in self.world.animals.iter_mut().enumerate() {
+            let vision = animal.eye.process_senses(
+                idx,
+                animal.species,
+                animal.rotation,
+                &surroundings,
             );
 
-            let rotation = response[1].clamp(
-                -ROTATION_ACCEL,
-                ROTATION_ACCEL,
-            );
+            let (speed, rotation) = animal.brain.propagate(vision, &mut animal.memory);
 
-            animal.speed =
-                (animal.speed + speed).clamp(SPEED_MIN, SPEED_MAX);
+            animal.speed = (animal.speed + speed).clamp(
+                self.config.sim_speed_min,
+                self.config.sim_speed_max,
+            );
 
             animal.rotation = na::Rotation2::new(
                 animal.rotation.angle() + rotation,
             );
+
+            animal.energy -= self.config.energy_cost_base
+                + self.config.energy_cost_speed * animal.speed
+                + self.config.energy_cost_rotation * rotation.abs();
+        }
+    }
+
+    /// Returns (index, fitness) of each living animal of given species.
+    fn fitnesses(&self, species: Species) -> impl Iterator<Item = (usize, f32)> + '_ {
+        self.world
+            .animals
+            .iter()
+            .enumerate()
+            .filter(move |(_, animal)| animal.species == species)
+            .map(move |(idx, animal)| (idx, fitness(&self.config, animal)))
+    }
+
+    /// Returns positions of all the animals and of the prey alone, for
+    /// the eyes to look at.
+    fn positions(&self) -> (Vec<na::Point2<f32>>, Vec<na::Point2<f32>>) {
+        let animals = self.world.animals.iter().map(|animal| animal.position).collect();
+
+        let prey = self
+            .world
+            .animals
+            .iter()
+            .filter(|animal| animal.species == Species::Prey)
+            .map(|animal| animal.position)
+            .collect();
+
+        (animals, prey)
+    }
+
+    fn process_hunting(&mut self) {
+        let (predators, prey): (Vec<_>, Vec<_>) = (0..self.world.animals.len())
+            .partition(|&idx| self.world.animals[idx].species == Species::Predator);
+
+        if predators.is_empty() {
+            return;
+        }
+
+        let prey_positions: Vec<_> = prey
+            .iter()
+            .map(|&idx| self.world.animals[idx].position)
+            .collect();
+
+        let grid = Grid::from_positions(&prey_positions);
+
+        for predator in predators {
+            let position = self.world.animals[predator].position;
+
+            for nearby in grid.query(position, self.config.predator_catch_size) {
+                let victim = prey[nearby];
+
+                // Prey that's been caught during this very step is still
+                // around, but it can't be caught again
+                if !self.world.animals[victim].is_alive() {
+                    continue;
+                }
+
+                let distance = self
+                    .config
+                    .world_boundary
+                    .distance(position, prey_positions[nearby]);
+
+                if distance > self.config.predator_catch_size {
+                    continue;
+                }
+
+                let victim = &mut self.world.animals[victim];
+                victim.energy = 0.0;
+                victim.caught = true;
+
+                let predator = &mut self.world.animals[predator];
+                predator.satiation += 1;
+                predator.energy = (predator.energy + self.config.energy_food)
+                    .min(self.config.energy_max);
+
+                self.world.prey_caught += 1;
+            }
         }
     }
 
     fn process_movements(&mut self) {
         for animal in &mut self.world.animals {
             animal.position += animal.rotation * na::Vector2::new(animal.speed, 0.0);
+            animal.position = self.config.world_boundary.confine(animal.position);
+        }
+    }
+
+    fn process_deaths(&mut self) {
+        let lifetime_max = self.config.sim_lifetime_max;
+
+        for animal in &mut self.world.animals {
+            animal.lifetime += 1;
+
+            if animal.lifetime >= lifetime_max {
+                animal.energy = 0.0;
+            }
+        }
+
+        if self.world.animals.iter().all(Animal::is_alive) {
+            return;
+        }
+
+        let (alive, dead): (Vec<_>, Vec<_>) = self
+            .world
+            .animals
+            .drain(..)
+            .partition(|animal| animal.is_alive());
+
+        self.world.animals = alive;
+
+        // With continuous evolution there's no generation to evaluate
+        // the dead at, so they're gone for good
+        if self.config.sim_evolution == Evolution::Generational {
+            self.world.dead_animals.extend(dead);
+        }
+    }
+
+    fn process_reproduction(&mut self) {
+        let mut newborns = Vec::new();
+
+        for idx in 0..self.world.animals.len() {
+            if self.world.animals.len() + newborns.len() >= self.config.world_animals_max {
+                break;
+            }
+
+            let parent = &self.world.animals[idx];
+
+            if parent.satiation < self.config.ga_reproduction_satiation {
+                continue;
+            }
+
+            let mate = self.find_mate(idx);
+
+            let mut child = match self.config.brain_kind {
+                BrainKind::Layered => self.chromosome_offspring(idx, mate),
+                BrainKind::Neat => self.genome_offspring(idx, mate),
+            };
+
+            child.position = self.world.animals[idx].position;
 
-            animal.position.x = na::wrap(animal.position.x, 0.0, 1.0);
-            animal.position.y = na::wrap(animal.position.y, 0.0, 1.0);
+            self.world.animals[idx].satiation -= self.config.ga_reproduction_satiation;
+            newborns.push(child);
+        }
+
+        self.world.animals.extend(newborns);
+
+        // Keeps the population (of prey, at least) from dying out entirely
+        let prey = self
+            .world
+            .animals
+            .iter()
+            .filter(|animal| animal.species == Species::Prey)
+            .count();
+
+        for _ in prey..self.config.world_animals_min {
+            let animal = Animal::random(&self.config, &mut self.rng);
+            self.world.animals.push(animal);
+        }
+    }
+
+    /// Creates a child of the animal at `idx` and its mate (or - when
+    /// there's no mate around - of that animal alone).
+    fn chromosome_offspring(&mut self, idx: usize, mate: Option<usize>) -> Animal {
+        let species = self.world.animals[idx].species;
+
+        let species_ga = match species {
+            Species::Prey => &self.ga,
+            Species::Predator => &self.predator_ga,
+        };
+
+        let parent_a = self.world.animals[idx].as_chromosome();
+
+        let parent_b = mate
+            .map(|mate| self.world.animals[mate].as_chromosome())
+            .unwrap_or_else(|| parent_a.clone());
+
+        let child = species_ga.offspring(
+            &mut self.rng,
+            &parent_a,
+            &parent_b,
+            self.generation,
+        );
+
+        Animal::from_chromosome(&self.config, species, child, &mut self.rng)
+    }
+
+    /// `BrainKind::Neat` counterpart of `chromosome_offspring()`.
+    fn genome_offspring(&mut self, idx: usize, mate: Option<usize>) -> Animal {
+        let species = self.world.animals[idx].species;
+
+        let parent_a = AnimalGenome::from_animal(&self.config, &self.world.animals[idx]);
+
+        let parent_b = mate
+            .map(|mate| AnimalGenome::from_animal(&self.config, &self.world.animals[mate]))
+            .unwrap_or_else(|| AnimalGenome::from_animal(&self.config, &self.world.animals[idx]));
+
+        let (fitter, other) =
+            if ga::NeatIndividual::fitness(&parent_a) >= ga::NeatIndividual::fitness(&parent_b) {
+                (&parent_a, &parent_b)
+            } else {
+                (&parent_b, &parent_a)
+            };
+
+        let species_neat = match species {
+            Species::Prey => &mut self.neat,
+            Species::Predator => &mut self.predator_neat,
+        };
+
+        let child = species_neat.offspring(
+            &mut self.rng,
+            ga::NeatIndividual::genome(fitter),
+            ga::NeatIndividual::genome(other),
+        );
+
+        Animal::from_genome(&self.config, species, child, &mut self.rng)
+    }
+
+    /// Returns the nearest animal of the same species within
+    /// `ga_mate_range`, if any.
+    fn find_mate(&self, idx: usize) -> Option<usize> {
+        let position = self.world.animals[idx].position;
+        let species = self.world.animals[idx].species;
+
+        self.world
+            .animals
+            .iter()
+            .enumerate()
+            .filter(|(other, animal)| *other != idx && animal.species == species)
+            .map(|(other, animal)| {
+                let distance = self.config.world_boundary.distance(position, animal.position);
+                (other, distance)
+            })
+            .filter(|(_, distance)| *distance <= self.config.ga_mate_range)
+            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+            .map(|(other, _)| other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod from_seed {
+        use super::*;
+
+        fn world_after_generations(seed: u64, generations: usize) -> String {
+            let config = Config {
+                sim_generation_length: 30,
+                ..Default::default()
+            };
+
+            let steps = generations * (config.sim_generation_length + 1);
+            let mut simulation = Simulation::from_seed(seed, config);
+
+            for _ in 0..steps {
+                simulation.step();
+            }
+
+            format!("{:?}", simulation.world())
+        }
+
+        #[test]
+        fn same_seed_yields_identical_worlds() {
+            assert_eq!(world_after_generations(42, 3), world_after_generations(42, 3));
+        }
+
+        #[test]
+        fn different_seeds_yield_different_worlds() {
+            assert_ne!(world_after_generations(42, 3), world_after_generations(43, 3));
+        }
+    }
+
+    mod try_from_seed {
+        use super::*;
+
+        fn error(config: Config) -> Option<Error> {
+            Simulation::try_from_seed(42, config).err()
+        }
+
+        #[test]
+        fn given_valid_config() {
+            assert_eq!(error(Config::default()), None);
+        }
+
+        #[test]
+        fn given_invalid_config() {
+            assert_eq!(
+                error(Config { ga_mut_chance: 1.5, ..Default::default() }),
+                Some(Error::Genetic(ga::Error::InvalidChance(1.5))),
+            );
+
+            assert_eq!(
+                error(Config { brain_neurons: 0, ..Default::default() }),
+                Some(Error::Neural(nn::Error::EmptyLayer)),
+            );
+
+            assert_eq!(
+                error(Config { eye_cells: 0, brain_kind: BrainKind::Neat, ..Default::default() }),
+                Some(Error::InvalidEye),
+            );
+
+            assert_eq!(
+                error(Config { eye_fov_range: f32::NAN, ..Default::default() }),
+                Some(Error::InvalidEye),
+            );
+
+            assert_eq!(
+                error(Config { eye_see_walls: true, ..Default::default() }),
+                Some(Error::InvisibleWalls),
+            );
+
+            assert_eq!(
+                error(Config { sim_speed_min: 0.01, ..Default::default() }),
+                Some(Error::InvalidSpeed),
+            );
+
+            assert_eq!(
+                error(Config { sim_speed_max: f32::NAN, ..Default::default() }),
+                Some(Error::InvalidSpeed),
+            );
+
+            assert_eq!(
+                error(Config { sim_speed_accel: -0.1, ..Default::default() }),
+                Some(Error::InvalidAcceleration),
+            );
+
+            assert_eq!(
+                error(Config { sim_rotation_accel: f32::NAN, ..Default::default() }),
+                Some(Error::InvalidAcceleration),
+            );
+
+            assert_eq!(
+                error(Config { world_animals_min: 100, ..Default::default() }),
+                Some(Error::InvalidPopulationBounds),
+            );
+
+            assert_eq!(
+                error(Config { sim_lifetime_max: 0, ..Default::default() }),
+                Some(Error::EmptyLifetime),
+            );
+
+            assert_eq!(
+                error(Config { fitness_survival: -1.0, ..Default::default() }),
+                Some(Error::InvalidParameter { name: "fitness_survival", value: -1.0 }),
+            );
+
+            assert_eq!(
+                error(Config { predator_fitness_survival: -0.5, ..Default::default() }),
+                Some(Error::InvalidParameter { name: "predator_fitness_survival", value: -0.5 }),
+            );
+
+            assert_eq!(
+                error(Config { food_size: -0.01, ..Default::default() }),
+                Some(Error::InvalidParameter { name: "food_size", value: -0.01 }),
+            );
+
+            assert_eq!(
+                error(Config { energy_cost_speed: -1.0, ..Default::default() }),
+                Some(Error::InvalidParameter { name: "energy_cost_speed", value: -1.0 }),
+            );
+
+            // (NaN != NaN, so the error's matched on its name only)
+            assert!(matches!(
+                error(Config { neat_compatibility_threshold: f32::NAN, ..Default::default() }),
+                Some(Error::InvalidParameter { name: "neat_compatibility_threshold", .. }),
+            ));
+
+            assert_eq!(
+                error(Config { world_animals: 0, ..Default::default() }),
+                Some(Error::NoAnimals),
+            );
+
+            assert_eq!(
+                error(Config { sim_generation_length: 0, ..Default::default() }),
+                Some(Error::EmptyGeneration),
+            );
+
+            assert_eq!(
+                error(Config {
+                    ga_mutation: Mutation::Annealed { initial_sigma: 0.5, decay: 1.5 },
+                    ..Default::default()
+                }),
+                Some(Error::Genetic(ga::Error::InvalidParameter { name: "decay", value: 1.5 })),
+            );
+
+            assert_eq!(
+                error(Config {
+                    ga_selection: Selection::Tournament { size: 0 },
+                    ..Default::default()
+                }),
+                Some(Error::Genetic(ga::Error::InvalidTournamentSize)),
+            );
+
+            assert_eq!(
+                error(Config {
+                    ga_selection: Selection::Truncation { fraction: 0.0 },
+                    ..Default::default()
+                }),
+                Some(Error::Genetic(ga::Error::InvalidFraction(0.0))),
+            );
+
+            assert_eq!(
+                error(Config { ga_crossover: Crossover::KPoint { k: 0 }, ..Default::default() }),
+                Some(Error::Genetic(ga::Error::InvalidPointCount)),
+            );
+
+            assert_eq!(
+                error(Config {
+                    ga_crossover: Crossover::Arithmetic { alpha: -1.0 },
+                    ..Default::default()
+                }),
+                Some(Error::Genetic(ga::Error::InvalidParameter { name: "alpha", value: -1.0 })),
+            );
+
+            assert_eq!(
+                error(Config {
+                    ga_crossover: Crossover::SimulatedBinary { eta: -1.0 },
+                    ..Default::default()
+                }),
+                Some(Error::Genetic(ga::Error::InvalidParameter { name: "eta", value: -1.0 })),
+            );
+        }
+    }
+
+    mod evolve {
+        use super::*;
+
+        #[test]
+        fn carries_elites_over() {
+            let config = Config {
+                ga_elite_count: 1,
+                ..Default::default()
+            };
+
+            let mut simulation = Simulation::from_seed(42, config);
+
+            for (satiation, animal) in simulation.world.animals.iter_mut().enumerate() {
+                animal.satiation = satiation;
+            }
+
+            let fittest = simulation.world.animals.last().unwrap().as_chromosome();
+
+            simulation.evolve();
+
+            assert_eq!(simulation.world.animals[0].as_chromosome(), fittest);
+        }
+    }
+
+    mod mutation {
+        use super::*;
+
+        fn evolved(mutation: Mutation, generation: usize) -> Vec<ga::Chromosome> {
+            let config = Config {
+                ga_mutation: mutation,
+                ga_mut_chance: 0.5,
+                ..Default::default()
+            };
+
+            let mut simulation = Simulation::from_seed(42, config);
+            simulation.generation = generation;
+
+            for (satiation, animal) in simulation.world.animals.iter_mut().enumerate() {
+                animal.satiation = satiation;
+            }
+
+            simulation.evolve();
+
+            simulation.world.animals.iter().map(Animal::as_chromosome).collect()
+        }
+
+        #[test]
+        fn anneals_with_generations() {
+            let annealed = evolved(Mutation::Annealed { initial_sigma: 0.5, decay: 0.5 }, 1);
+            let normal = evolved(Mutation::Normal { sigma: 0.25 }, 1);
+            let gaussian = evolved(Mutation::Gaussian, 1);
+
+            assert_eq!(format!("{:?}", annealed), format!("{:?}", normal));
+            assert_ne!(format!("{:?}", annealed), format!("{:?}", gaussian));
+        }
+    }
+
+    mod selection {
+        use super::*;
+
+        #[test]
+        fn evolves_populations_where_nobody_has_scored() {
+            for &selection in &[
+                Selection::Roulette,
+                Selection::Tournament { size: 3 },
+                Selection::Rank,
+                Selection::Truncation { fraction: 0.5 },
+            ] {
+                let config = Config {
+                    ga_selection: selection,
+                    ga_mut_chance: 0.0,
+                    ..Default::default()
+                };
+
+                let mut simulation = Simulation::from_seed(42, config);
+                let parents: Vec<_> =
+                    simulation.world.animals.iter().map(Animal::as_chromosome).collect();
+
+                simulation.evolve();
+
+                // Without mutations, each child's gene comes from one of
+                // the parents - had the population been started anew
+                // instead, the genes would've been random
+                for child in &simulation.world.animals {
+                    let child = child.as_chromosome();
+
+                    for (idx, gene) in child.iter().enumerate() {
+                        assert!(
+                            parents.iter().any(|parent| parent[idx] == *gene),
+                            "{:?}",
+                            selection,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    mod energy {
+        use super::*;
+
+        fn config() -> Config {
+            // Depending on their speed, animals starve after 20 to 100
+            // steps, unless they find something to eat
+            Config {
+                energy_initial: 0.1,
+                energy_cost_speed: 1.0,
+                fitness_survival: 1.0,
+                sim_generation_length: 150,
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn starving_animals_die() {
+            let mut simulation = Simulation::from_seed(42, config());
+
+            for _ in 0..30 {
+                simulation.step();
+            }
+
+            let world = simulation.world();
+
+            assert!(!world.dead_animals.is_empty());
+            assert_eq!(world.animals.len() + world.dead_animals.len(), 40);
+            assert!(world.animals.iter().all(Animal::is_alive));
+            assert!(world.dead_animals.iter().all(|animal| !animal.is_alive()));
+        }
+
+        #[test]
+        fn dead_animals_take_part_in_evolution() {
+            let mut simulation = Simulation::from_seed(42, config());
+
+            let stats = loop {
+                if let Some(stats) = simulation.step() {
+                    break stats;
+                }
+            };
+
+            assert_eq!(stats.generation, 0);
+            assert_eq!(simulation.world().animals.len(), 40);
+            assert!(simulation.world().dead_animals.is_empty());
+        }
+
+        #[test]
+        fn fitness_accounts_for_survival_time() {
+            let config = Config {
+                fitness_survival: 10.0,
+                sim_generation_length: 100,
+                ..Default::default()
+            };
+
+            let mut simulation = Simulation::from_seed(42, config);
+            let animal = &mut simulation.world.animals[0];
+
+            animal.satiation = 3;
+            animal.lifetime = 25;
+
+            let individual = AnimalIndividual::from_animal(&simulation.config, animal);
+
+            assert_eq!(ga::Individual::fitness(&individual), 5.5);
+        }
+    }
+
+    mod process_collisions {
+        use super::*;
+        use test_case::test_case;
+
+        #[test_case(Boundary::Toroidal, 1)]
+        #[test_case(Boundary::Walled, 0)]
+        fn across_edge(boundary: Boundary, expected_satiation: usize) {
+            let config = Config {
+                world_animals: 1,
+                world_foods: 1,
+                world_boundary: boundary,
+                ..Default::default()
+            };
+
+            let mut simulation = Simulation::from_seed(42, config);
+            simulation.world.animals[0].position = na::Point2::new(0.998, 0.5);
+            simulation.world.foods[0].position = na::Point2::new(0.002, 0.5);
+
+            let mut grid = Grid::from_foods(&simulation.world.foods);
+            simulation.process_collisions(&mut grid);
+
+            assert_eq!(simulation.world.animals[0].satiation, expected_satiation);
+        }
+    }
+
+    mod senses {
+        use super::*;
+
+        #[test]
+        fn extra_channels_grow_the_brain() {
+            let config = Config {
+                eye_see_animals: true,
+                eye_see_walls: true,
+                world_boundary: Boundary::Walled,
+                ..Default::default()
+            };
+
+            let mut simulation = Simulation::from_seed(42, config.clone());
+
+            for _ in 0..10 {
+                simulation.step();
+            }
+
+            let plain = Animal::random(&Config::default(), &mut simulation.rng);
+            let seeing = &simulation.world.animals[0];
+
+            // Each extra channel adds `eye_cells` inputs, each of them
+            // connected to every hidden neuron
+            assert_eq!(
+                seeing.as_chromosome().len() - plain.as_chromosome().len(),
+                2 * config.eye_cells * config.brain_neurons,
+            );
+        }
+    }
+
+    mod predators {
+        use super::*;
+
+        fn config() -> Config {
+            Config {
+                food_size: 0.1,
+                world_foods: 200,
+                predator_brain_neurons: 6,
+                predator_catch_size: 0.05,
+                fitness_survival: 1.0,
+                predator_fitness_survival: 1.0,
+                sim_generation_length: 20,
+                world_animals: 30,
+                world_predators: 10,
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn catch_prey() {
+            let config = Config {
+                world_animals: 2,
+                world_predators: 1,
+                ..config()
+            };
+
+            let mut simulation = Simulation::from_seed(42, config.clone());
+            let predator = simulation.world.animals[2].position;
+            simulation.world.animals[0].position = predator;
+
+            simulation.process_hunting();
+
+            let animals = &simulation.world.animals;
+
+            assert!(animals[0].caught);
+            assert!(!animals[0].is_alive());
+            assert!(!animals[1].caught);
+            assert_eq!(animals[2].satiation, 1);
+            assert_eq!(simulation.world.prey_caught, 1);
+
+            let prey = AnimalIndividual::from_animal(&config, &animals[0]);
+            assert_eq!(ga::Individual::fitness(&prey), 0.0);
+        }
+
+        #[test]
+        fn species_evolve_independently() {
+            let config = config();
+            let mut simulation = Simulation::from_seed(42, config.clone());
+
+            let reports: Vec<_> = (0..63)
+                .filter_map(|_| simulation.step())
+                .collect();
+
+            assert_eq!(reports.len(), 3);
+            assert!(reports.iter().all(|stats| stats.predators.is_some()));
+
+            let count = |species| {
+                simulation
+                    .world
+                    .animals
+                    .iter()
+                    .filter(|animal| animal.species == species)
+                    .count()
+            };
+
+            assert_eq!(count(Species::Prey), config.world_animals);
+            assert_eq!(count(Species::Predator), config.world_predators);
+
+            let chromosome_len = |species| {
+                simulation
+                    .world
+                    .animals
+                    .iter()
+                    .find(|animal| animal.species == species)
+                    .unwrap()
+                    .as_chromosome()
+                    .len()
+            };
+
+            assert!(chromosome_len(Species::Predator) < chromosome_len(Species::Prey));
+        }
+
+        #[test]
+        fn species_have_own_fitness() {
+            let config = Config {
+                fitness_survival: 10.0,
+                predator_fitness_survival: 2.0,
+                fitness_caught: 1.0,
+                sim_generation_length: 100,
+                ..config()
+            };
+
+            let mut simulation = Simulation::from_seed(42, config.clone());
+
+            for animal in &mut simulation.world.animals {
+                animal.satiation = 3;
+                animal.lifetime = 50;
+                animal.caught = animal.species == Species::Prey;
+            }
+
+            let fitness_of = |species| {
+                let animal = simulation
+                    .world
+                    .animals
+                    .iter()
+                    .find(|animal| animal.species == species)
+                    .unwrap();
+
+                fitness(&config, animal)
+            };
+
+            assert_eq!(fitness_of(Species::Prey), 3.0 + 5.0 - 1.0);
+            assert_eq!(fitness_of(Species::Predator), 3.0 + 1.0);
+        }
+
+        #[test]
+        fn species_have_own_mutation() {
+            let config = Config {
+                ga_mut_chance: 0.0,
+                predator_ga_mut_chance: 1.0,
+                world_animals: 1,
+                world_predators: 1,
+                ..config()
+            };
+
+            // With a single parent (crossed over with itself), only the
+            // mutation can make offspring differ
+            let mut simulation = Simulation::from_seed(42, config);
+
+            // (populations where nobody has scored start anew instead)
+            for animal in &mut simulation.world.animals {
+                animal.satiation = 1;
+            }
+
+            let before: Vec<_> =
+                simulation.world.animals.iter().map(Animal::as_chromosome).collect();
+
+            simulation.evolve();
+
+            let after = |species| {
+                simulation
+                    .world
+                    .animals
+                    .iter()
+                    .find(|animal| animal.species == species)
+                    .unwrap()
+                    .as_chromosome()
+            };
+
+            assert_eq!(after(Species::Prey), before[0]);
+            assert_ne!(after(Species::Predator), before[1]);
+        }
+    }
+
+    mod export_brain {
+        use super::*;
+
+        fn trained(config: Config) -> Simulation {
+            let mut simulation = Simulation::from_seed(42, config);
+
+            for _ in 0..50 {
+                simulation.step();
+            }
+
+            simulation
+        }
+
+        #[test]
+        fn test() {
+            let config = Config {
+                brain_recurrent: true,
+                ..Default::default()
+            };
+
+            let source = trained(config.clone());
+            let exported = source.export_fittest_brain(Species::Prey).unwrap();
+
+            let fittest = source
+                .world
+                .animals
+                .iter()
+                .map(|animal| fitness(&config, animal))
+                .fold(0.0, f32::max);
+
+            assert_eq!(exported.species, Species::Prey);
+            assert_eq!(exported.generation, 0);
+            approx::assert_relative_eq!(exported.fitness, fittest);
+
+            let mut target = Simulation::from_seed(7, config);
+            let idx = target.try_import_brain(&exported).unwrap();
+
+            assert_eq!(target.world.animals.len(), 40);
+            assert_eq!(target.world.animals[idx].species, Species::Prey);
+
+            assert_eq!(
+                format!("{:?}", target.export_brain(idx).unwrap().network),
+                format!("{:?}", exported.network),
+            );
+        }
+
+        #[test]
+        fn neat_brains() {
+            let config = Config {
+                brain_kind: BrainKind::Neat,
+                sim_generation_length: 20,
+                ..Default::default()
+            };
+
+            let source = trained(config.clone());
+            let exported = source.export_brain(0).unwrap();
+
+            assert_eq!(exported.generation, 2);
+
+            let mut target = Simulation::from_seed(7, config);
+            let idx = target.try_import_brain(&exported).unwrap();
+
+            assert_eq!(
+                target.world.animals[idx].brain.genome(),
+                source.world.animals[0].brain.genome(),
+            );
+        }
+
+        #[test]
+        fn neat_brains_across_runs() {
+            let config = Config {
+                brain_kind: BrainKind::Neat,
+                sim_generation_length: 20,
+                neat_add_node_chance: 0.5,
+                neat_add_connection_chance: 0.5,
+                ..Default::default()
+            };
+
+            let mut source = Simulation::from_seed(42, config.clone());
+            source.train_generations(5);
+
+            let exported = source.export_brain(0).unwrap();
+
+            // Target's history has diverged from the source's one, so the
+            // same innovation numbers stand for different connections
+            let mut target = Simulation::from_seed(7, config.clone());
+            target.train_generations(5);
+
+            let idx = target.try_import_brain(&exported).unwrap();
+
+            // Genome got renumbered, but it's still the same network
+            let outputs = |brain: &Brain| {
+                let vision = (0..Eye::from_config(&config).inputs()).map(|i| i as f32 / 10.0);
+                brain.activations(vision.collect(), &mut Default::default()).1
+            };
+
+            assert_eq!(
+                outputs(&target.world.animals[idx].brain),
+                outputs(&source.world.animals[0].brain),
+            );
+
+            // ... and its innovation numbers mean the same as everybody
+            // else's, so it can be crossed over with them
+            let mut links = std::collections::BTreeMap::new();
+
+            for animal in &target.world.animals {
+                for connection in animal.brain.genome().unwrap().connections() {
+                    let link = (connection.from, connection.to);
+                    assert_eq!(*links.entry(connection.innovation).or_insert(link), link);
+                }
+            }
+
+            target.train_generations(10);
+        }
+
+        #[cfg(feature = "json")]
+        #[test]
+        fn rejects_cyclic_neat_brains() {
+            let config = Config {
+                brain_kind: BrainKind::Neat,
+                ..Default::default()
+            };
+
+            let exported = trained(config.clone()).export_brain(0).unwrap();
+            let mut json: serde_json::Value =
+                serde_json::from_str(&exported.to_json().unwrap()).unwrap();
+
+            let outputs = Eye::from_config(&config).inputs();
+            let connection = |innovation, from, to| {
+                serde_json::json!({
+                    "innovation": innovation,
+                    "from": from,
+                    "to": to,
+                    "weight": 1.0,
+                    "enabled": false,
+                })
+            };
+
+            let connections = json["network"]["Neat"]["genome"]["connections"]
+                .as_array_mut()
+                .unwrap();
+            connections.push(connection(1000, outputs, outputs + 1));
+            connections.push(connection(1001, outputs + 1, outputs));
+
+            let exported = ExportedBrain::from_json(&json.to_string()).unwrap();
+            let mut target = Simulation::from_seed(7, config);
+
+            assert_eq!(
+                target.try_import_brain(&exported).err(),
+                Some(Error::Neural(nn::Error::Cycle)),
+            );
+        }
+
+        #[test]
+        fn adds_missing_species() {
+            let exported = trained(Config {
+                world_predators: 2,
+                ..Default::default()
+            })
+            .export_fittest_brain(Species::Predator)
+            .unwrap();
+
+            let mut target = Simulation::from_seed(7, Default::default());
+
+            assert_eq!(target.try_import_brain(&exported), Ok(40));
+            assert_eq!(target.world.animals[40].species, Species::Predator);
+        }
+
+        #[test]
+        fn rejects_incompatible_brains() {
+            let exported = trained(Config::default()).export_brain(0).unwrap();
+
+            for config in &[
+                Config { eye_cells: 5, ..Default::default() },
+                Config { brain_neurons: 10, ..Default::default() },
+                Config { brain_recurrent: true, ..Default::default() },
+                Config { brain_hidden_activation: nn::Activation::Sigmoid, ..Default::default() },
+                Config { brain_kind: BrainKind::Neat, ..Default::default() },
+            ] {
+                let mut target = Simulation::from_seed(7, config.clone());
+
+                assert_eq!(
+                    target.try_import_brain(&exported).err(),
+                    Some(Error::IncompatibleBrain),
+                );
+            }
+        }
+
+        #[test]
+        fn rejects_mismatched_weights() {
+            let mut exported = trained(Config::default()).export_brain(0).unwrap();
+
+            if let ExportedBrainNetwork::Layered(network) = &mut exported.network {
+                network.weights.pop();
+            }
+
+            let mut target = Simulation::from_seed(7, Default::default());
+
+            assert_eq!(
+                target.try_import_brain(&exported).err(),
+                Some(Error::Neural(nn::Error::NotEnoughWeights)),
+            );
+        }
+
+        #[cfg(feature = "json")]
+        #[test]
+        fn json() {
+            let exported = trained(Config::default()).export_brain(0).unwrap();
+            let imported = ExportedBrain::from_json(&exported.to_json().unwrap()).unwrap();
+
+            assert_eq!(format!("{:?}", imported), format!("{:?}", exported));
+        }
+    }
+
+    mod train {
+        use super::*;
+
+        fn config() -> Config {
+            Config {
+                sim_generation_length: 20,
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn test() {
+            let mut simulation = Simulation::from_seed(42, config());
+
+            for _ in 0..5 {
+                simulation.step();
+            }
+
+            let stats = simulation.train().unwrap();
+
+            assert_eq!(stats.generation, 0);
+            assert_eq!(simulation.generation(), 1);
+            assert_eq!(simulation.age, 0);
+        }
+
+        #[test]
+        fn matches_stepping() {
+            let mut trained = Simulation::from_seed(42, config());
+            let mut stepped = Simulation::from_seed(42, config());
+
+            let actual = trained.train_generations(3);
+            let expected: Vec<_> = std::iter::from_fn(|| Some(stepped.step()))
+                .flatten()
+                .take(3)
+                .collect();
+
+            assert_eq!(actual.len(), 3);
+            assert_eq!(format!("{:?}", actual), format!("{:?}", expected));
+            assert_eq!(trained.generation(), 3);
+        }
+    }
+
+    mod animal_details {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let config = Config {
+                eye_see_animals: true,
+                ..Default::default()
+            };
+
+            let mut simulation = Simulation::from_seed(42, config.clone());
+
+            for _ in 0..10 {
+                simulation.step();
+            }
+
+            let details = simulation.animal_details(3).unwrap();
+            let animal = &simulation.world.animals[3];
+
+            assert_eq!(details.vision.len(), 2 * config.eye_cells);
+            assert_eq!(details.hidden.len(), config.brain_neurons);
+            assert_eq!(details.outputs.len(), 2);
+            assert_eq!(details.speed, animal.speed);
+            assert_eq!(details.satiation, animal.satiation);
+
+            assert!(simulation.animal_details(config.world_animals).is_none());
+        }
+
+        #[test]
+        fn matches_next_step() {
+            let config = Config {
+                brain_recurrent: true,
+                ..Default::default()
+            };
+
+            let mut simulation = Simulation::from_seed(42, config);
+
+            for _ in 0..10 {
+                simulation.step();
+            }
+
+            let details = simulation.animal_details(0).unwrap();
+            let speed = simulation.world.animals[0].speed;
+
+            // Asking for details doesn't change anything...
+            assert_eq!(
+                format!("{:?}", simulation.animal_details(0).unwrap()),
+                format!("{:?}", details)
+            );
+
+            // ... and they describe what's going to happen next
+            let r0 = details.outputs[0].clamp(0.0, 1.0) - 0.5;
+            let r1 = details.outputs[1].clamp(0.0, 1.0) - 0.5;
+
+            simulation.step();
+
+            let expected = (speed + (r0 + r1).clamp(-0.2, 0.2)).clamp(0.001, 0.005);
+
+            approx::assert_relative_eq!(simulation.world.animals[0].speed, expected);
+        }
+
+        #[test]
+        fn neat_brains() {
+            let config = Config {
+                brain_kind: BrainKind::Neat,
+                ..Default::default()
+            };
+
+            let simulation = Simulation::from_seed(42, config);
+            let details = simulation.animal_details(0).unwrap();
+
+            // Fresh NEAT genomes have no hidden nodes
+            assert!(details.hidden.is_empty());
+            assert_eq!(details.outputs.len(), 2);
+        }
+    }
+
+    mod recurrent_brains {
+        use super::*;
+
+        #[test]
+        fn remember_until_reborn() {
+            let config = Config {
+                brain_recurrent: true,
+                food_size: 0.1,
+                world_foods: 200,
+                sim_generation_length: 10,
+                ..Default::default()
+            };
+
+            let mut simulation = Simulation::from_seed(42, config.clone());
+            let plain = Animal::random(&Config::default(), &mut simulation.rng);
+
+            assert_eq!(
+                simulation.world.animals[0].as_chromosome().len()
+                    - plain.as_chromosome().len(),
+                config.brain_neurons * config.brain_neurons,
+            );
+
+            for _ in 0..10 {
+                simulation.step();
+            }
+
+            assert_ne!(simulation.world.animals[0].memory, nn::Memory::default());
+
+            // Evolution happens here
+            simulation.step();
+
+            assert_eq!(simulation.world.animals[0].memory, nn::Memory::default());
+        }
+    }
+
+    mod neat_brains {
+        use super::*;
+
+        fn config() -> Config {
+            Config {
+                brain_kind: BrainKind::Neat,
+                food_size: 0.1,
+                world_foods: 200,
+                sim_generation_length: 10,
+                neat_add_node_chance: 0.5,
+                neat_add_connection_chance: 0.5,
+                ..Default::default()
+            }
+        }
+
+        fn hidden_nodes(animal: &Animal) -> usize {
+            animal
+                .brain
+                .genome()
+                .unwrap()
+                .nodes()
+                .iter()
+                .filter(|node| node.kind == ga::NodeKind::Hidden)
+                .count()
+        }
+
+        #[test]
+        fn grow_during_evolution() {
+            let config = config();
+            let mut simulation = Simulation::from_seed(42, config.clone());
+
+            assert!(simulation.world.animals.iter().all(|animal| hidden_nodes(animal) == 0));
+
+            for _ in 0..55 {
+                simulation.step();
+            }
+
+            assert_eq!(simulation.generation(), 5);
+            assert_eq!(simulation.world.animals.len(), config.world_animals);
+            assert!(simulation.world.animals.iter().any(|animal| hidden_nodes(animal) > 0));
+        }
+
+        #[test]
+        fn reproduce_continuously() {
+            let config = Config {
+                ga_reproduction_satiation: 2,
+                sim_evolution: Evolution::Continuous,
+                world_animals: 20,
+                world_animals_min: 10,
+                world_animals_max: 30,
+                ..config()
+            };
+
+            let mut simulation = Simulation::from_seed(42, config.clone());
+
+            for _ in 0..100 {
+                simulation.step();
+            }
+
+            assert!(simulation.world.animals.len() > config.world_animals);
+            assert!(simulation.world.animals.iter().any(|animal| hidden_nodes(animal) > 0));
+        }
+    }
+
+    mod step {
+        use super::*;
+
+        #[test]
+        fn reports_statistics_once_per_generation() {
+            // Plenty of large foods, so that the roulette wheel always
+            // has someone with a non-zero fitness to pick
+            let config = Config {
+                food_size: 0.1,
+                world_foods: 200,
+                sim_generation_length: 10,
+                ..Default::default()
+            };
+
+            let mut simulation = Simulation::from_seed(42, config);
+
+            let reports: Vec<_> = (0..33)
+                .filter_map(|_| simulation.step())
+                .collect();
+
+            assert_eq!(reports.len(), 3);
+            assert_eq!(simulation.generation(), 3);
+
+            for (generation, stats) in reports.iter().enumerate() {
+                assert_eq!(stats.generation, generation);
+                assert!(stats.min_fitness <= stats.median_fitness);
+                assert!(stats.median_fitness <= stats.max_fitness);
+            }
+        }
+    }
+
+    mod continuous {
+        use super::*;
+
+        fn config() -> Config {
+            Config {
+                food_size: 0.1,
+                world_foods: 200,
+                ga_reproduction_satiation: 2,
+                sim_evolution: Evolution::Continuous,
+                sim_generation_length: 50,
+                sim_lifetime_max: 120,
+                world_animals: 20,
+                world_animals_min: 10,
+                world_animals_max: 30,
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn keeps_population_within_bounds() {
+            let config = config();
+            let mut simulation = Simulation::from_seed(42, config.clone());
+            let mut grown = false;
+
+            for _ in 0..300 {
+                simulation.step();
+
+                let animals = simulation.world().animals().len();
+
+                assert!(animals >= config.world_animals_min);
+                assert!(animals <= config.world_animals_max);
+
+                grown |= animals > config.world_animals;
+            }
+
+            assert!(grown, "no animal has been born");
+        }
+
+        #[test]
+        fn keeps_prey_from_dying_out_among_predators() {
+            let config = Config {
+                world_predators: 15,
+                ..config()
+            };
+
+            let mut simulation = Simulation::from_seed(42, config.clone());
+
+            simulation
+                .world
+                .animals
+                .retain(|animal| animal.species == Species::Predator);
+
+            simulation.step();
+
+            let prey = simulation
+                .world
+                .animals
+                .iter()
+                .filter(|animal| animal.species == Species::Prey)
+                .count();
+
+            assert!(prey >= config.world_animals_min);
+
+            // ... so that statistics (which are about prey) keep coming
+            let stats = simulation.train_generations(2);
+            assert_eq!(stats.len(), 2);
+        }
+
+        #[test]
+        fn discards_dead_animals() {
+            let mut simulation = Simulation::from_seed(42, config());
+
+            for _ in 0..300 {
+                simulation.step();
+
+                assert!(simulation.world.dead_animals.is_empty());
+                assert!(simulation.world.animals.iter().all(Animal::is_alive));
+            }
+        }
+
+        #[test]
+        fn reports_statistics_once_per_period() {
+            let mut simulation = Simulation::from_seed(42, config());
+
+            let reports: Vec<_> = (0..153)
+                .filter_map(|_| simulation.step())
+                .collect();
+
+            assert_eq!(reports.len(), 3);
+            assert_eq!(simulation.generation(), 3);
+
+            for (generation, stats) in reports.iter().enumerate() {
+                assert_eq!(stats.generation, generation);
+                assert!(stats.food_eaten > 0);
+            }
         }
     }
 }
@@ -0,0 +1,202 @@
+use crate::*;
+use serde::{de, Deserializer, Serializer};
+
+/// Everything that's needed to resume a simulation bit-for-bit.
+///
+/// The genetic algorithm itself is not a part of the snapshot - it's
+/// stateless and gets re-created from the config; NEAT, on the other
+/// hand, carries its history of innovations, so it's saved along.
+#[derive(Serialize)]
+struct SnapshotRef<'a> {
+    config: &'a Config,
+    world: &'a World,
+    generation: usize,
+    age: usize,
+    rng: &'a ChaCha8Rng,
+    neat: Option<&'a ga::Neat>,
+    predator_neat: Option<&'a ga::Neat>,
+}
+
+#[derive(Deserialize)]
+struct Snapshot {
+    config: Config,
+    world: World,
+    generation: usize,
+    age: usize,
+    rng: ChaCha8Rng,
+    #[serde(default)]
+    neat: Option<ga::Neat>,
+    #[serde(default)]
+    predator_neat: Option<ga::Neat>,
+}
+
+impl Serialize for Simulation {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        SnapshotRef {
+            config: &self.config,
+            world: &self.world,
+            generation: self.generation,
+            age: self.age,
+            rng: &self.rng,
+            neat: Some(&self.neat),
+            predator_neat: Some(&self.predator_neat),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Simulation {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let snapshot = Snapshot::deserialize(deserializer)?;
+
+        // (`Self::new()` panics on invalid configs, and snapshots are
+        // user-provided)
+        snapshot.config.validate().map_err(de::Error::custom)?;
+
+        let mut simulation = Self::new(
+            snapshot.config,
+            snapshot.world,
+            snapshot.generation,
+            snapshot.age,
+            snapshot.rng,
+        );
+
+        // (snapshots taken before NEAT came along don't have it)
+        if let Some(neat) = snapshot.neat {
+            simulation.neat = neat;
+        }
+
+        if let Some(predator_neat) = snapshot.predator_neat {
+            simulation.predator_neat = predator_neat;
+        }
+
+        Ok(simulation)
+    }
+}
+
+#[cfg(feature = "json")]
+impl Simulation {
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+#[cfg(feature = "bincode")]
+impl Simulation {
+    pub fn to_bincode(&self) -> bincode::Result<Vec<u8>> {
+        bincode::serialize(self)
+    }
+
+    pub fn from_bincode(bytes: &[u8]) -> bincode::Result<Self> {
+        bincode::deserialize(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn simulation() -> Simulation {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let config = Config {
+            sim_generation_length: 50,
+            ..Default::default()
+        };
+
+        Simulation::random(config, &mut rng)
+    }
+
+    /// Steps both simulations past a generation boundary and checks
+    /// they've ended up in the very same state
+    fn assert_resumes_identically(mut original: Simulation, mut resumed: Simulation) {
+        for _ in 0..120 {
+            original.step();
+            resumed.step();
+        }
+
+        assert_eq!(original.generation, resumed.generation);
+        assert_eq!(original.age, resumed.age);
+        assert_eq!(format!("{:?}", original.world), format!("{:?}", resumed.world));
+        assert_eq!(original.rng, resumed.rng);
+    }
+
+    #[cfg(feature = "json")]
+    mod json {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut original = simulation();
+
+            for _ in 0..30 {
+                original.step();
+            }
+
+            let resumed = Simulation::from_json(&original.to_json().unwrap()).unwrap();
+
+            assert_resumes_identically(original, resumed);
+        }
+
+        #[test]
+        fn neat_brains() {
+            let config = Config {
+                brain_kind: BrainKind::Neat,
+                sim_generation_length: 50,
+                neat_add_node_chance: 0.5,
+                neat_add_connection_chance: 0.5,
+                ..Default::default()
+            };
+
+            let mut original = Simulation::from_seed(42, config);
+
+            for _ in 0..80 {
+                original.step();
+            }
+
+            let resumed = Simulation::from_json(&original.to_json().unwrap()).unwrap();
+
+            assert_resumes_identically(original, resumed);
+        }
+
+        #[test]
+        fn invalid_config() {
+            let mut original = simulation();
+            original.config.world_animals = 0;
+
+            let err = Simulation::from_json(&original.to_json().unwrap())
+                .err()
+                .expect("snapshot with an invalid config got accepted");
+
+            assert!(err.to_string().starts_with(&Error::NoAnimals.to_string()));
+        }
+    }
+
+    #[cfg(feature = "bincode")]
+    mod bincode {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut original = simulation();
+
+            for _ in 0..30 {
+                original.step();
+            }
+
+            let resumed = Simulation::from_bincode(&original.to_bincode().unwrap()).unwrap();
+
+            assert_resumes_identically(original, resumed);
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use crate::*;
+
+/// Kind of an animal; each species evolves on its own, with its own
+/// genetic algorithm (see `Config::predator_ga_mut_chance` and
+/// `predator_ga_mut_coeff`), brain topology (`predator_brain_neurons`)
+/// and fitness function (`predator_fitness_survival`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Species {
+    /// Eats food and tries not to get caught
+    Prey,
+
+    /// Hunts prey; doesn't eat food
+    Predator,
+}
+
+impl Default for Species {
+    fn default() -> Self {
+        Self::Prey
+    }
+}
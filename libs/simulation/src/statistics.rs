@@ -0,0 +1,63 @@
+use crate::*;
+
+/// Report of how a single generation has performed, produced whenever
+/// the population gets evolved.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GenerationStatistics {
+    /// Index of the generation this report describes, starting at 0
+    pub generation: usize,
+
+    /// Total number of foods eaten by the whole population
+    pub food_eaten: usize,
+
+    pub min_fitness: f32,
+    pub max_fitness: f32,
+    pub mean_fitness: f32,
+    pub median_fitness: f32,
+    pub std_dev_fitness: f32,
+
+    /// Same report, but for predators - present only when the world has
+    /// any
+    #[serde(default)]
+    pub predators: Option<PredatorStatistics>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PredatorStatistics {
+    /// Total number of prey caught by all the predators
+    pub prey_caught: usize,
+
+    pub min_fitness: f32,
+    pub max_fitness: f32,
+    pub mean_fitness: f32,
+    pub median_fitness: f32,
+    pub std_dev_fitness: f32,
+}
+
+impl GenerationStatistics {
+    crate fn new(generation: usize, food_eaten: usize, stats: &ga::Statistics) -> Self {
+        Self {
+            generation,
+            food_eaten,
+            min_fitness: stats.min_fitness(),
+            max_fitness: stats.max_fitness(),
+            mean_fitness: stats.mean_fitness(),
+            median_fitness: stats.median_fitness(),
+            std_dev_fitness: stats.std_dev_fitness(),
+            predators: None,
+        }
+    }
+
+    crate fn with_predators(mut self, prey_caught: usize, stats: &ga::Statistics) -> Self {
+        self.predators = Some(PredatorStatistics {
+            prey_caught,
+            min_fitness: stats.min_fitness(),
+            max_fitness: stats.max_fitness(),
+            mean_fitness: stats.mean_fitness(),
+            median_fitness: stats.median_fitness(),
+            std_dev_fitness: stats.std_dev_fitness(),
+        });
+
+        self
+    }
+}
@@ -1,21 +1,40 @@
 use crate::*;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct World{
     crate animals: Vec<Animal>,
-    crate foods: Vec<Food>
+    crate foods: Vec<Food>,
+
+    /// Animals that have starved during the current generation; they
+    /// don't take part in the simulation anymore, but they still get to
+    /// be evaluated when the generation ends
+    #[serde(default)]
+    crate dead_animals: Vec<Animal>,
+
+    /// Number of foods eaten since the last generation has ended
+    #[serde(default)]
+    crate food_eaten: usize,
+
+    /// Number of prey caught since the last generation has ended
+    #[serde(default)]
+    crate prey_caught: usize,
 }
 
 impl World {
-    pub fn random(rng: &mut dyn RngCore) -> Self {
-        let animals = (0..40)
-            .map(|_| Animal::random(rng))
+    pub fn random(config: &Config, rng: &mut dyn RngCore) -> Self {
+        let mut animals: Vec<_> = (0..config.world_animals)
+            .map(|_| Animal::random(config, rng))
             .collect();
 
-        let foods = (0..60)
+        let foods = (0..config.world_foods)
             .map(|_| Food::random(rng))
             .collect();
 
+        animals.extend(
+            (0..config.world_predators)
+                .map(|_| Animal::random_species(config, Species::Predator, rng)),
+        );
+
         // ^ Our algorithm allows for animals and foods to overlap, so
         // | it's hardly ideal - but good enough for our purposes.
         // |
@@ -25,7 +44,13 @@ impl World {
         // | https://en.wikipedia.org/wiki/Supersampling
         // ---
 
-        Self { animals, foods }
+        Self {
+            animals,
+            foods,
+            dead_animals: Vec::new(),
+            food_eaten: 0,
+            prey_caught: 0,
+        }
     }
     pub fn animals(&self) -> &[Animal] {
         &self.animals
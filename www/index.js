@@ -1,8 +1,27 @@
 import * as sim from "lib-simulation-wasm";
+import { memory } from "lib-simulation-wasm/lib_simulation_wasm_bg";
+
+// Layout of the world buffer (see `updateWorldBuffer()`)
+const ANIMAL_STRIDE = 4;
+const FOOD_STRIDE = 2;
 
 const simulation = new sim.Simulation();
 console.log(simulation.world())
 
+// Simulation's config can be edited as JSON and applied by restarting the
+// simulation; invalid configs get reported and leave it running as it was.
+const config = document.getElementById('config');
+config.value = JSON.stringify(simulation.config(), null, 2);
+
+document.getElementById('reset').addEventListener('click', () => {
+    try {
+        simulation.reset(JSON.parse(config.value));
+        selected = null;
+    } catch (err) {
+        alert(err);
+    }
+});
+
 const viewport = document.getElementById('viewport');
 const viewportWidth = viewport.width;
 const viewportHeight = viewport.height;
@@ -17,7 +36,7 @@ const ctxt = viewport.getContext('2d')
 ctxt.scale(viewportScale, viewportScale);
 
 CanvasRenderingContext2D.prototype.drawTriangle =
-    function (x, y, size, rotation) {
+    function (x, y, size, rotation, color) {
         this.beginPath();
 
         this.moveTo(
@@ -39,7 +58,7 @@ CanvasRenderingContext2D.prototype.drawTriangle =
             x + Math.cos(rotation) * size * 1.5,
             y + Math.sin(rotation) * size * 1.5,
         );
-        this.fillStyle = 'rgb(255, 255, 200)';
+        this.fillStyle = color;
         this.fill();
     };
 
@@ -51,30 +70,147 @@ CanvasRenderingContext2D.prototype.drawCircle =
         this.fill();
     };
 
+// Index of the animal whose brain is shown next to the viewport (if any);
+// click on the viewport to pick the nearest one.
+//
+// Animals don't have any stable identifiers, so - since indices get
+// shuffled whenever animals are born or die - the selection is dropped
+// when a generation ends or the number of animals changes.
+let selected = null;
+let selectedAmong = 0;
+
+function select(index) {
+    selected = index;
+    selectedAmong = simulation.worldAnimals();
+}
+
+function dropStaleSelection(generationEnded) {
+    if (generationEnded || simulation.worldAnimals() !== selectedAmong) {
+        selected = null;
+    }
+}
+
+viewport.addEventListener('click', event => {
+    const x = event.offsetX / viewportWidth;
+    const y = event.offsetY / viewportHeight;
+    let nearest = Infinity;
+
+    simulation.world().animals.forEach((animal, index) => {
+        const distance = Math.hypot(animal.x - x, animal.y - y);
+
+        if (distance < nearest) {
+            nearest = distance;
+            select(index);
+        }
+    });
+});
+
+function formatValues(values) {
+    return values.map(value => value.toFixed(2)).join(' ');
+}
+
+function redrawDetails() {
+    const details = selected === null ? null : simulation.animalDetails(selected);
+
+    if (!details) {
+        document.getElementById('details').textContent = '';
+        return;
+    }
+
+    document.getElementById('details').textContent =
+        `animal ${selected}\n` +
+        `vision:    ${formatValues(details.vision)}\n` +
+        `hidden:    ${formatValues(details.hidden)}\n` +
+        `outputs:   ${formatValues(details.outputs)}\n` +
+        `speed:     ${details.speed.toFixed(4)}\n` +
+        `satiation: ${details.satiation}`;
+}
+
+function logStats(stats) {
+    console.log(
+        `generation ${stats.generation}: ` +
+        `min=${stats.min_fitness.toFixed(2)}, ` +
+        `max=${stats.max_fitness.toFixed(2)}, ` +
+        `mean=${stats.mean_fitness.toFixed(2)}, ` +
+        `median=${stats.median_fitness.toFixed(2)}, ` +
+        `std dev=${stats.std_dev_fitness.toFixed(2)}, ` +
+        `food eaten=${stats.food_eaten}`
+    );
+}
+
+// Jumps ahead in evolution without drawing the intermediate frames
+document.getElementById('train').addEventListener('click', () => {
+    for (const stats of simulation.trainGenerations(10)) {
+        logStats(stats);
+    }
+
+    selected = null;
+});
+
+// Brains can be carried over between runs (or browser tabs) by copying
+// the exported JSON and importing it back
+const brain = document.getElementById('brain');
+
+document.getElementById('export-brain').addEventListener('click', () => {
+    brain.value = simulation.exportFittestBrain(false) || '';
+});
+
+document.getElementById('import-brain').addEventListener('click', () => {
+    try {
+        select(simulation.importBrain(brain.value));
+    } catch (err) {
+        alert(err);
+    }
+});
+
 function redraw() {
     ctxt.clearRect(0, 0, viewportWidth, viewportHeight);
 
-    simulation.step();
+    const stats = simulation.step();
+
+    if (stats) {
+        logStats(stats);
+    }
+
+    dropStaleSelection(Boolean(stats));
 
-    const world = simulation.world();
+    // The view has to be re-created each frame, since wasm's memory might
+    // have grown (and thus moved) since the previous one
+    simulation.updateWorldBuffer();
 
-    for (const food of world.foods) {
+    const world = new Float32Array(
+        memory.buffer,
+        simulation.worldBufferPtr(),
+        simulation.worldBufferLen(),
+    );
+
+    const animals = simulation.worldAnimals();
+    const foods = world.subarray(animals * ANIMAL_STRIDE);
+
+    for (let i = 0; i < foods.length; i += FOOD_STRIDE) {
         ctxt.drawCircle(
-            food.x * viewportWidth,
-            food.y * viewportHeight,
+            foods[i] * viewportWidth,
+            foods[i + 1] * viewportHeight,
             (0.01 / 2.0) * viewportWidth,
         );
     }
 
-    for (const animal of simulation.world().animals) {
+    for (let index = 0; index < animals; index += 1) {
+        const i = index * ANIMAL_STRIDE;
+
         ctxt.drawTriangle(
-            animal.x * viewportWidth,
-            animal.y * viewportHeight,
+            world[i] * viewportWidth,
+            world[i + 1] * viewportHeight,
             0.01 * viewportWidth,
-            animal.rotation,
+            world[i + 2],
+            index === selected
+                ? 'rgb(80, 255, 80)'
+                : world[i + 3] ? 'rgb(255, 80, 80)' : 'rgb(255, 255, 200)',
         );
     }
 
+    redrawDetails();
+
     // requestAnimationFrame() schedules code only for the next frame.
     //
     // Because we want for our simulation to continue forever, we've